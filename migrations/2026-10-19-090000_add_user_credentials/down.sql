@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+alter table users drop column password_hash;
+alter table users drop column username;
+alter table users drop column email;
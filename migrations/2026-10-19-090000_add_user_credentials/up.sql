@@ -0,0 +1,5 @@
+-- Your SQL goes here
+
+alter table users add column email varchar(255) unique;
+alter table users add column username varchar(30) unique;
+alter table users add column password_hash varchar(255);
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+
+alter table users drop column locale;
+alter table users drop column avatar_emoji;
+alter table users drop column avatar_color;
+
+alter table users alter column name type varchar(50);
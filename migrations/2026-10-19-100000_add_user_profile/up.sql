@@ -0,0 +1,8 @@
+-- Your SQL goes here
+
+-- display names may contain emoji and combining characters, which take more code points
+alter table users alter column name type varchar(100);
+
+alter table users add column avatar_color varchar(7);
+alter table users add column avatar_emoji varchar(32);
+alter table users add column locale varchar(16);
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+
+alter table users_sessions add column nickname varchar(100);
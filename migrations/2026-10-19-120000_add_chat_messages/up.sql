@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+create table chat_messages (
+    id bigserial primary key,
+    session_id varchar(20) not null references sessions(id) on delete cascade,
+    user_id varchar(36) not null references users(id) on delete cascade,
+    message varchar(500) not null,
+    created_at timestamp not null default now()
+);
+
+create index chat_messages_session_id_idx on chat_messages (session_id, id);
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+
+drop table sessions_word_packs;
+
+drop table word_pack_words;
+
+drop table word_packs;
@@ -0,0 +1,20 @@
+-- Your SQL goes here
+
+create table word_packs (
+    id varchar(50) primary key,
+    name varchar(100) not null,
+    category varchar(50) not null,
+    created_at timestamp not null default now()
+);
+
+create table word_pack_words (
+    pack_id varchar(50) not null references word_packs(id) on delete cascade,
+    word varchar(255) not null,
+    primary key (pack_id, word)
+);
+
+create table sessions_word_packs (
+    session_id varchar(20) not null references sessions(id) on delete cascade,
+    pack_id varchar(50) not null references word_packs(id) on delete cascade,
+    primary key (session_id, pack_id)
+);
@@ -0,0 +1,20 @@
+-- This file should undo anything in `up.sql`
+
+drop index words_session_id_normalized_idx;
+
+alter table words drop column normalized;
+
+update words w
+set word = b.word
+from words_before_normalization b
+where b.rewritten_to is not null
+  and w.session_id = b.session_id
+  and w.word = b.rewritten_to;
+
+insert into words (word, created_at, session_id, user_id)
+select word, created_at, session_id, user_id
+from words_before_normalization
+where rewritten_to is null
+on conflict do nothing;
+
+drop table words_before_normalization;
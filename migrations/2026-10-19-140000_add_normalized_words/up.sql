@@ -0,0 +1,42 @@
+-- Your SQL goes here
+
+alter table words add column normalized varchar(255);
+
+update words
+set normalized = lower(normalize(regexp_replace(trim(word), '\s+', ' ', 'g'), NFC));
+
+-- originals of the words removed or rewritten below, `down.sql` puts them back
+create table words_before_normalization (
+    word varchar(255) not null,
+    created_at timestamp not null,
+    session_id varchar(20) not null references sessions(id) on delete cascade,
+    user_id varchar(36) not null references users(id) on delete cascade,
+    -- null if the word was removed as a duplicate
+    rewritten_to varchar(255),
+    primary key (word, session_id)
+);
+
+-- keep the oldest of words that only differed in case or whitespace
+with removed as (
+    delete from words a
+    using words b
+    where a.session_id = b.session_id
+      and a.normalized = b.normalized
+      and (a.created_at, a.word) > (b.created_at, b.word)
+    returning a.word, a.created_at, a.session_id, a.user_id
+)
+insert into words_before_normalization (word, created_at, session_id, user_id)
+select word, created_at, session_id, user_id
+from removed;
+
+insert into words_before_normalization (word, created_at, session_id, user_id, rewritten_to)
+select word, created_at, session_id, user_id, normalize(regexp_replace(trim(word), '\s+', ' ', 'g'), NFC)
+from words
+where word <> normalize(regexp_replace(trim(word), '\s+', ' ', 'g'), NFC);
+
+update words
+set word = normalize(regexp_replace(trim(word), '\s+', ' ', 'g'), NFC);
+
+alter table words alter column normalized set not null;
+
+create unique index words_session_id_normalized_idx on words (session_id, normalized);
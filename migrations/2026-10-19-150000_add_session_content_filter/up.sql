@@ -0,0 +1,6 @@
+-- Your SQL goes here
+
+alter table sessions add column content_filter varchar(10) not null default 'medium';
+
+alter table sessions add constraint sessions_content_filter_check
+check (content_filter in ('off', 'low', 'medium', 'high'));
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+
+alter table users alter column username type varchar(30);
+
+alter table word_packs drop column language;
+
+alter table sessions drop column language;
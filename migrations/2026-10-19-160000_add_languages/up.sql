@@ -0,0 +1,9 @@
+-- Your SQL goes here
+
+alter table sessions add column language varchar(16) not null default 'en';
+
+alter table word_packs add column language varchar(16) not null default 'en';
+
+-- usernames are limited in graphemes, which can take several chars each,
+-- the validation also caps them at 120 chars
+alter table users alter column username type varchar(120);
@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+
+drop index sessions_public_last_activity_at_idx;
+
+drop index sessions_public_created_at_idx;
+
+alter table sessions drop column last_activity_at;
+
+alter table sessions drop column phase;
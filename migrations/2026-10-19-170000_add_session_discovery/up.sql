@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+alter table sessions add column phase varchar(10) not null default 'lobby';
+
+alter table sessions add constraint sessions_phase_check
+check (phase in ('lobby', 'playing', 'finished'));
+
+alter table sessions add column last_activity_at timestamp not null default current_timestamp;
+
+update sessions set last_activity_at = created_at;
+
+create index sessions_public_created_at_idx on sessions (public, created_at desc, id desc);
+
+create index sessions_public_last_activity_at_idx on sessions (public, last_activity_at desc, id desc);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+drop table session_waitlist;
+
+alter table sessions drop column max_players;
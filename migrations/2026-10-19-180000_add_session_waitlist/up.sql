@@ -0,0 +1,16 @@
+-- Your SQL goes here
+
+alter table sessions add column max_players integer not null default 12;
+
+alter table sessions add constraint sessions_max_players_check
+check (max_players between 2 and 100);
+
+create table session_waitlist (
+    session_id varchar(20) not null references sessions(id) on delete cascade,
+    user_id varchar(36) not null references users(id) on delete cascade,
+    nickname varchar(100),
+    created_at timestamp not null default current_timestamp,
+    primary key (session_id, user_id)
+);
+
+create index session_waitlist_session_id_created_at_idx on session_waitlist (session_id, created_at);
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+drop table game_players;
+
+drop table games;
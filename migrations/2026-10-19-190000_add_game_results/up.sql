@@ -0,0 +1,24 @@
+-- Your SQL goes here
+
+create table games (
+    id uuid primary key,
+    session_id varchar(20) not null references sessions(id) on delete cascade,
+    started_at timestamp not null,
+    finished_at timestamp not null default current_timestamp
+);
+
+create index games_session_id_idx on games (session_id);
+
+create table game_players (
+    game_id uuid not null references games(id) on delete cascade,
+    user_id varchar(36) not null references users(id) on delete cascade,
+    score integer not null default 0,
+    won boolean not null default false,
+    words_performed integer not null default 0,
+    words_guessed integer not null default 0,
+    guesses integer not null default 0,
+    perform_time_ms bigint not null default 0,
+    primary key (game_id, user_id)
+);
+
+create index game_players_user_id_idx on game_players (user_id);
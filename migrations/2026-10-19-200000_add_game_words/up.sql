@@ -0,0 +1,11 @@
+-- Your SQL goes here
+
+create table game_words (
+    game_id uuid not null references games(id) on delete cascade,
+    position integer not null,
+    word varchar(255) not null,
+    performed_by varchar(36) references users(id) on delete set null,
+    guessed_by varchar(36) references users(id) on delete set null,
+    duration_ms bigint not null default 0,
+    primary key (game_id, position)
+);
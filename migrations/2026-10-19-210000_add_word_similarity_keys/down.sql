@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+
+drop index words_session_id_similarity_key_idx;
+
+alter table words drop column similarity_key;
@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+alter table words add column similarity_key text;
+
+-- the same folding as the guess checking, except for combining marks outside U+0300 to U+036F
+update words
+set similarity_key = trim(regexp_replace(
+    lower(regexp_replace(normalize(word, NFKD), '[\u0300-\u036f]', '', 'g')),
+    '[^[:alnum:]]+', ' ', 'g'
+));
+
+alter table words alter column similarity_key set not null;
+
+create index words_session_id_similarity_key_idx on words (session_id, similarity_key);
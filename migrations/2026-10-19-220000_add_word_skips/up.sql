@@ -0,0 +1,4 @@
+-- Your SQL goes here
+
+-- how often the performers skipped the word before it was guessed
+alter table game_words add column skips integer not null default 0;
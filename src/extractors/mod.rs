@@ -1 +1,3 @@
+pub mod optional_json;
+pub mod optional_user_id;
 pub mod user_id;
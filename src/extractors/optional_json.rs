@@ -0,0 +1,62 @@
+use std::pin::Pin;
+
+use actix_web::{dev::Payload, web::Bytes, FromRequest, HttpRequest};
+use actix_web_validator::error::flatten_errors;
+use futures_util::Future;
+use serde::de::DeserializeOwned;
+use validator::Validate;
+
+use crate::models::custom_api_errors::ApiError;
+
+/// Validated json body that may be left out. Only an empty body counts as missing,
+/// a malformed or invalid one is rejected with 400 like with the `Json` extractor.
+#[derive(Debug)]
+pub struct OptionalJson<T>(pub Option<T>);
+
+impl<T> OptionalJson<T> {
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + Validate + 'static> FromRequest for OptionalJson<T> {
+    type Error = ApiError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let bytes_fut = Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let bytes = bytes_fut.await.map_err(|err| ApiError::BadRequest {
+                message: err.to_string(),
+            })?;
+
+            if bytes.iter().all(u8::is_ascii_whitespace) {
+                return Ok(OptionalJson(None));
+            }
+
+            let body: T = serde_json::from_slice(&bytes).map_err(|err| ApiError::BadRequest {
+                message: err.to_string(),
+            })?;
+
+            body.validate().map_err(|errors| {
+                let flattened_errors = flatten_errors(&errors);
+
+                match flattened_errors.first() {
+                    Some((_, field, error)) => ApiError::Validation {
+                        message: error
+                            .message
+                            .as_deref()
+                            .unwrap_or("An unknown validation error occurred")
+                            .to_string(),
+                        code: error.code.to_string(),
+                        field: Some(field.clone()),
+                    },
+                    None => ApiError::from(errors),
+                }
+            })?;
+
+            Ok(OptionalJson(Some(body)))
+        })
+    }
+}
@@ -0,0 +1,29 @@
+use std::pin::Pin;
+
+use actix_web::{dev::Payload, FromRequest, HttpRequest};
+use futures_util::Future;
+
+use crate::models::custom_api_errors::ApiError;
+
+use super::user_id::UserId;
+
+/// Id of the authenticated user if there is one, for endpoints that also serve anonymous requests
+#[derive(Debug, Clone)]
+pub struct OptionalUserId(pub Option<String>);
+
+impl FromRequest for OptionalUserId {
+    type Error = ApiError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let user_id_fut = UserId::from_request(req, payload);
+
+        Box::pin(async {
+            match user_id_fut.await {
+                Ok(user_id) => Ok(OptionalUserId(Some(user_id.0))),
+                Err(ApiError::Unauthorized { .. }) => Ok(OptionalUserId(None)),
+                Err(e) => Err(e),
+            }
+        })
+    }
+}
@@ -1,44 +1,46 @@
 use std::pin::Pin;
 
 use actix_session::SessionExt;
-use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use actix_web::{dev::Payload, http::header, FromRequest, HttpRequest};
 use futures_util::Future;
 
 use crate::{
-    models::{custom_api_errors::ApiError, user::NewUser},
-    routes::SESSION_USER_ID,
-    AppContext,
+    models::custom_api_errors::ApiError, routes::SESSION_USER_ID,
+    utils::tokens::verify_access_token,
 };
 
+/// Id of the authenticated user, taken from an `Authorization: Bearer` access token or
+/// the session cookie. Rejects the request with 401 if there is none.
+/// Users are only created by `POST /api/auth`.
 #[derive(Debug, Clone)]
 pub struct UserId(pub String);
 
 impl UserId {
-    async fn extract(req: HttpRequest) -> Result<UserId, ApiError> {
-        let session = req.get_session();
-        let ctx = req
-            .app_data::<web::Data<AppContext>>()
-            .ok_or(ApiError::internal(
-                "Could not get AppContext from request".to_string(),
-            ))?;
+    pub fn extract(req: &HttpRequest) -> Result<UserId, ApiError> {
+        if let Some(token) = Self::bearer_token(req) {
+            return verify_access_token(token).map(UserId::from);
+        }
 
-        let user_id_opt = session.get::<String>("user_id")?;
+        Self::from_session(req)
+    }
 
-        match user_id_opt {
-            Some(id) => Ok(id.into()),
-            None => {
-                let new_user = NewUser {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    name: None,
-                };
+    /// Id of the user logged in with the session cookie, access tokens are ignored
+    pub fn from_session(req: &HttpRequest) -> Result<UserId, ApiError> {
+        let session = req.get_session();
 
-                let user = ctx.db.create_user(new_user).await?;
+        let user_id_opt = session.get::<String>(SESSION_USER_ID)?;
 
-                session.insert(SESSION_USER_ID, user.id.clone())?;
+        user_id_opt.map(UserId::from).ok_or(ApiError::Unauthorized {
+            message: "Please authenticate first".to_string(),
+        })
+    }
 
-                Ok(user.id.into())
-            }
-        }
+    fn bearer_token(req: &HttpRequest) -> Option<&str> {
+        req.headers()
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?
+            .strip_prefix("Bearer ")
     }
 }
 
@@ -65,13 +67,8 @@ impl FromRequest for UserId {
     type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
 
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let req_clone = req.clone();
+        let res = UserId::extract(req);
 
-        Box::pin(async {
-            match UserId::extract(req_clone).await {
-                Ok(user_id) => Ok(user_id),
-                Err(e) => Err(e),
-            }
-        })
+        Box::pin(async { res })
     }
 }
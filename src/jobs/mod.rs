@@ -0,0 +1,2 @@
+pub mod user_cleanup;
+pub mod word_packs;
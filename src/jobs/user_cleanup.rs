@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use actix_web::rt;
+
+use crate::repositories::database::Database;
+
+/// How often abandoned users are cleaned up
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How old a user must be before it counts as abandoned
+const ABANDONED_AFTER_HOURS: i64 = 24;
+
+/// Periodically deletes anonymous users that never set a name and never joined a session
+pub fn spawn(db: Database) {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(CLEANUP_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let created_before =
+                chrono::Utc::now().naive_utc() - chrono::Duration::hours(ABANDONED_AFTER_HOURS);
+
+            match db.delete_abandoned_users(created_before).await {
+                Ok(0) => {}
+                Ok(deleted) => log::info!("Deleted {} abandoned users", deleted),
+                Err(err) => log::error!("Could not delete abandoned users: {}", err),
+            }
+        }
+    });
+}
@@ -0,0 +1,179 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use actix_web::rt;
+use anyhow::{anyhow, Context};
+
+use crate::{
+    models::{
+        session::DEFAULT_LANGUAGE,
+        word_pack::{NewWordPack, WordPackFile},
+    },
+    repositories::database::Database,
+    utils::envs,
+};
+
+/// Longest word that fits into the words tables
+const MAX_WORD_LENGTH: usize = 255;
+
+/// Imports the word packs shipped in the word packs directory once on startup.
+///
+/// `.json` files hold a single pack (`id`, `name`, `category`, `language`, `words`),
+/// `.csv` files hold rows of `pack_id,name,category,language,word` or `pack_id,name,category,word`
+/// below a header row, fields containing commas are quoted.
+/// Packs without a language are english in both formats.
+pub fn spawn(db: Database) {
+    rt::spawn(async move {
+        let dir = envs::word_packs_dir();
+
+        let packs = match read_packs(Path::new(&dir)) {
+            Ok(packs) => packs,
+            Err(err) => {
+                log::error!("Could not read word packs from {}: {:#}", dir, err);
+                return;
+            }
+        };
+
+        for pack in packs {
+            let id = pack.pack.id.clone();
+
+            match db.import_word_pack(pack.pack, pack.words).await {
+                Ok(0) => {}
+                Ok(imported) => log::info!("Imported {} words into word pack {}", imported, id),
+                Err(err) => log::error!("Could not import word pack {}: {}", id, err),
+            }
+        }
+    });
+}
+
+/// Files that can't be read are skipped, so one broken file doesn't keep the others from being imported
+fn read_packs(dir: &Path) -> anyhow::Result<Vec<WordPackFile>> {
+    let mut packs = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(err) => {
+                log::error!("Could not read word pack directory entry: {}", err);
+                continue;
+            }
+        };
+
+        match read_pack_file(&path) {
+            Ok(file_packs) => packs.extend(file_packs),
+            Err(err) => log::error!("Skipping word pack file {}: {:#}", path.display(), err),
+        }
+    }
+
+    Ok(packs
+        .into_iter()
+        .map(|pack| WordPackFile {
+            words: clean_words(pack.words),
+            pack: pack.pack,
+        })
+        .collect())
+}
+
+fn read_pack_file(path: &Path) -> anyhow::Result<Vec<WordPackFile>> {
+    let invalid_file = || format!("Invalid word pack file {}", path.display());
+
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => Ok(vec![
+            serde_json::from_str(&fs::read_to_string(path)?).with_context(invalid_file)?
+        ]),
+        Some("csv") => parse_csv(&fs::read_to_string(path)?).with_context(invalid_file),
+        _ => Ok(vec![]),
+    }
+}
+
+fn parse_csv(content: &str) -> anyhow::Result<Vec<WordPackFile>> {
+    let mut packs: BTreeMap<String, WordPackFile> = BTreeMap::new();
+
+    // the first line is the header
+    for (index, line) in content.lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let columns = split_csv_line(line).with_context(|| format!("Line {}", index + 1))?;
+
+        let (id, name, category, language, word) = match &columns[..] {
+            [id, name, category, language, word] => (id, name, category, language.as_str(), word),
+            // files from before packs had a language
+            [id, name, category, word] => (id, name, category, DEFAULT_LANGUAGE, word),
+            _ => return Err(anyhow!("Expected 4 or 5 columns in line {}", index + 1)),
+        };
+
+        packs
+            .entry(id.to_string())
+            .or_insert_with(|| WordPackFile {
+                pack: NewWordPack {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    category: category.to_string(),
+                    language: language.to_string(),
+                },
+                words: vec![],
+            })
+            .words
+            .push(word.to_string());
+    }
+
+    Ok(packs.into_values().collect())
+}
+
+/// Splits a line into trimmed fields. Fields with commas are quoted with `"`,
+/// a quote inside them is written as `""`. Quoted fields can't span several lines.
+fn split_csv_line(line: &str) -> anyhow::Result<Vec<String>> {
+    let mut fields = vec![];
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+
+        let mut field = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => return Err(anyhow!("Unterminated quoted field")),
+                }
+            }
+
+            while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+
+            if !matches!(chars.peek(), None | Some(',')) {
+                return Err(anyhow!("Unexpected characters after a quoted field"));
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                if c == '"' {
+                    return Err(anyhow!("Quotes are only allowed around a whole field"));
+                }
+
+                field.push(c);
+            }
+        }
+
+        fields.push(field.trim().to_string());
+
+        if chars.next().is_none() {
+            return Ok(fields);
+        }
+    }
+}
+
+fn clean_words(words: Vec<String>) -> Vec<String> {
+    let mut words: Vec<String> = words
+        .iter()
+        .map(|word| word.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|word| !word.is_empty() && word.chars().count() <= MAX_WORD_LENGTH)
+        .collect();
+
+    words.sort();
+    words.dedup();
+
+    words
+}
@@ -13,6 +13,7 @@ use websocket::server;
 
 mod api_configs;
 mod extractors;
+mod jobs;
 mod middlewares;
 mod models;
 mod repositories;
@@ -57,10 +58,14 @@ async fn main() -> std::io::Result<()> {
         cookie_secret: cookies_secret.clone(),
     });
 
-    let server = server::CharadeServer::new(db, cache).start();
+    jobs::user_cleanup::spawn(db.clone());
+    jobs::word_packs::spawn(db.clone());
+
+    let server = server::CharadeServer::new(db, cache.clone()).start();
 
     HttpServer::new(move || {
         App::new()
+            .wrap(middlewares::localization::Localization)
             .wrap(middleware::Compress::default())
             .app_data(json_config())
             .app_data(json_validator_config())
@@ -69,6 +74,7 @@ async fn main() -> std::io::Result<()> {
             .configure(config)
             .wrap(middlewares::session::session_middleware(
                 cookies_secret.to_owned(),
+                cache.clone(),
             ))
             .default_service(web::route().to(not_found))
             .wrap(cors_config())
@@ -0,0 +1,112 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    body::{self, BoxBody, EitherBody, MessageBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{AcceptLanguage, Header, Preference, CONTENT_TYPE},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::{models::custom_api_errors::ApiError, utils::i18n};
+
+/// First language of the `Accept-Language` header that has a message catalog
+fn preferred_language(req: &ServiceRequest) -> Option<String> {
+    let accept_language = AcceptLanguage::parse(req).ok()?;
+
+    accept_language
+        .ranked()
+        .into_iter()
+        .find_map(|preference| match preference {
+            Preference::Specific(tag) => {
+                let language = tag.primary_language().to_lowercase();
+
+                i18n::is_supported(&language).then_some(language)
+            }
+            Preference::Any => None,
+        })
+}
+
+/// Translates the message of error responses to the language requested with `Accept-Language`
+pub struct Localization;
+
+impl<S, B> Transform<S, ServiceRequest> for Localization
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = LocalizationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LocalizationMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct LocalizationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for LocalizationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        let language = preferred_language(&req).filter(|l| l != i18n::DEFAULT_LANGUAGE);
+
+        Box::pin(async move {
+            let res = service.call(req).await?;
+
+            let is_json_error = (res.status().is_client_error() || res.status().is_server_error())
+                && res
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.starts_with("application/json"))
+                    .unwrap_or(false);
+
+            let Some(language) = language.filter(|_| is_json_error) else {
+                return Ok(res.map_into_left_body());
+            };
+
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+
+            let bytes = body::to_bytes(body)
+                .await
+                .map_err(|_| ApiError::internal("Could not read error response".to_string()))?;
+
+            // bodies that are not an api error are passed on as they are
+            let bytes = match serde_json::from_slice::<ApiError>(&bytes) {
+                Ok(mut error) => {
+                    error.localize(&language);
+                    serde_json::to_vec(&error)?
+                }
+                Err(_) => bytes.to_vec(),
+            };
+
+            let res = res.set_body(BoxBody::new(bytes));
+
+            Ok(ServiceResponse::new(req, res).map_into_right_body())
+        })
+    }
+}
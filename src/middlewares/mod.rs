@@ -1 +1,4 @@
+pub mod localization;
+pub mod rate_limit;
 pub mod session;
+pub mod session_store;
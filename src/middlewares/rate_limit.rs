@@ -0,0 +1,216 @@
+use std::{
+    future::{ready, Ready},
+    net::IpAddr,
+    rc::Rc,
+    time::Duration,
+};
+
+use actix_web::{
+    dev::{forward_ready, ResourceDef, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header::X_FORWARDED_FOR, Method},
+    web, Error,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::{
+    extractors::user_id::UserId,
+    models::custom_api_errors::{ApiError, Result},
+    repositories::cache::Cache,
+    utils::envs,
+    AppContext,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Limit {
+    max_requests: u32,
+    window: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct RouteLimit {
+    method: Method,
+    resource: ResourceDef,
+    limit: Limit,
+}
+
+/// Sliding window rate limiter, counting requests per ip and per user id in Redis.
+///
+/// Every request counts against the default limit, requests matching a route added with
+/// [`RateLimiter::route`] additionally count against that route's limit.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    name: String,
+    default: Limit,
+    routes: Vec<RouteLimit>,
+}
+
+impl RateLimiter {
+    pub fn new(name: &str, max_requests: u32, window: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            default: Limit {
+                max_requests,
+                window,
+            },
+            routes: vec![],
+        }
+    }
+
+    /// Adds a limit for a single route, `pattern` uses the same syntax as actix routes
+    pub fn route(
+        mut self,
+        method: Method,
+        pattern: &str,
+        max_requests: u32,
+        window: Duration,
+    ) -> Self {
+        self.routes.push(RouteLimit {
+            method,
+            resource: ResourceDef::new(pattern),
+            limit: Limit {
+                max_requests,
+                window,
+            },
+        });
+
+        self
+    }
+
+    /// All limits the request counts against, with the name of their counters
+    fn limits_for(&self, req: &ServiceRequest) -> Vec<(String, Limit)> {
+        let mut limits = vec![(self.name.clone(), self.default)];
+
+        for route in &self.routes {
+            if route.method == req.method() && route.resource.is_match(req.path()) {
+                limits.push((
+                    format!(
+                        "{}:{}:{}",
+                        self.name,
+                        route.method,
+                        route.resource.pattern().unwrap_or_default()
+                    ),
+                    route.limit,
+                ));
+            }
+        }
+
+        limits
+    }
+}
+
+/// The peer address, unless the peer is one of the `TRUSTED_PROXIES`.
+/// Then it is the last address in `X-Forwarded-For` that is not a trusted proxy itself,
+/// the entries before it are set by the client and can't be trusted.
+fn client_ip(req: &ServiceRequest) -> Option<IpAddr> {
+    let peer = req.peer_addr()?.ip();
+    let trusted_proxies = envs::trusted_proxies();
+
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer);
+    }
+
+    let forwarded = req
+        .headers()
+        .get_all(X_FORWARDED_FOR)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+
+    let client = forwarded
+        .into_iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(ip))
+        .unwrap_or(peer);
+
+    Some(client)
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimiter
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RateLimiterMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimiterMiddleware {
+            service: Rc::new(service),
+            limiter: Rc::new(self.clone()),
+        }))
+    }
+}
+
+pub struct RateLimiterMiddleware<S> {
+    service: Rc<S>,
+    limiter: Rc<RateLimiter>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimiterMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let cache = req
+                .app_data::<web::Data<AppContext>>()
+                .map(|ctx| ctx.cache.clone())
+                .ok_or(ApiError::internal(
+                    "Could not get AppContext from request".to_string(),
+                ))?;
+
+            let mut clients = vec![];
+
+            if let Some(ip) = client_ip(&req) {
+                clients.push(format!("ip:{}", ip));
+            }
+
+            if let Ok(user_id) = UserId::extract(req.request()) {
+                clients.push(format!("user:{}", user_id.0));
+            }
+
+            let mut windows = vec![];
+
+            for (name, limit) in limiter.limits_for(&req) {
+                for client in &clients {
+                    windows.push((
+                        Cache::rate_limit_key(&name, client),
+                        limit.max_requests,
+                        limit.window,
+                    ));
+                }
+            }
+
+            match cache.hit_sliding_windows(&windows).await {
+                Ok(Some(retry_after)) => {
+                    return Err(ApiError::TooManyRequests {
+                        message: "Too many requests, please try again later".to_string(),
+                        retry_after: retry_after.as_secs_f64().ceil().max(1.0) as u64,
+                    }
+                    .into());
+                }
+                Ok(None) => {}
+                // an unavailable cache should not take the whole api down
+                Err(err) => log::error!("Could not check rate limit: {}", err),
+            }
+
+            service.call(req).await
+        })
+    }
+}
@@ -1,13 +1,29 @@
-use actix_session::{storage::CookieSessionStore, SessionMiddleware};
-use actix_web::cookie::Key;
+use actix_session::{
+    config::{PersistentSession, TtlExtensionPolicy},
+    SessionMiddleware,
+};
+use actix_web::cookie::{time::Duration, Key};
 
-pub fn session_middleware(secret_key: String) -> SessionMiddleware<CookieSessionStore> {
+use crate::{repositories::cache::Cache, utils::envs};
+
+use super::session_store::CacheSessionStore;
+
+pub fn session_middleware(
+    secret_key: String,
+    cache: Cache,
+) -> SessionMiddleware<CacheSessionStore> {
     SessionMiddleware::builder(
-        CookieSessionStore::default(),
+        CacheSessionStore::new(cache),
         Key::from(secret_key.as_bytes()),
     )
     .cookie_content_security(actix_session::config::CookieContentSecurity::Private)
     .cookie_name("token".to_string())
     .cookie_http_only(false)
+    // rolling expiry, every request keeps the session alive for another ttl
+    .session_lifecycle(
+        PersistentSession::default()
+            .session_ttl(Duration::seconds(envs::session_ttl_secs()))
+            .session_ttl_extension_policy(TtlExtensionPolicy::OnEveryRequest),
+    )
     .build()
 }
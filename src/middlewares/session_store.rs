@@ -0,0 +1,94 @@
+use std::{collections::HashMap, time::Duration};
+
+use actix_session::storage::{LoadError, SaveError, SessionKey, SessionStore, UpdateError};
+use actix_web::cookie::time::Duration as CookieDuration;
+use rand::{distributions::Alphanumeric, Rng};
+
+use crate::repositories::cache::Cache;
+
+/// Keeps the session state in Redis, the cookie only holds a random session key.
+/// This way sessions can be revoked server-side.
+pub struct CacheSessionStore {
+    cache: Cache,
+}
+
+impl CacheSessionStore {
+    pub fn new(cache: Cache) -> Self {
+        Self { cache }
+    }
+}
+
+fn generate_session_key() -> SessionKey {
+    let key: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(64)
+        .map(char::from)
+        .collect();
+
+    // 64 characters are well below the size limit of a session key
+    SessionKey::try_from(key).expect("Generated session key is too long")
+}
+
+fn to_std_duration(ttl: &CookieDuration) -> Duration {
+    Duration::from_millis(ttl.whole_milliseconds().max(0) as u64)
+}
+
+#[async_trait::async_trait(?Send)]
+impl SessionStore for CacheSessionStore {
+    async fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> Result<Option<HashMap<String, String>>, LoadError> {
+        self.cache
+            .get_auth_session(session_key.as_ref())
+            .await
+            .map_err(|e| LoadError::Other(anyhow::anyhow!(e.to_string())))
+    }
+
+    async fn save(
+        &self,
+        session_state: HashMap<String, String>,
+        ttl: &CookieDuration,
+    ) -> Result<SessionKey, SaveError> {
+        let session_key = generate_session_key();
+
+        self.cache
+            .set_auth_session(session_key.as_ref(), &session_state, to_std_duration(ttl))
+            .await
+            .map_err(|e| SaveError::Other(anyhow::anyhow!(e.to_string())))?;
+
+        Ok(session_key)
+    }
+
+    async fn update(
+        &self,
+        session_key: SessionKey,
+        session_state: HashMap<String, String>,
+        ttl: &CookieDuration,
+    ) -> Result<SessionKey, UpdateError> {
+        self.cache
+            .set_auth_session(session_key.as_ref(), &session_state, to_std_duration(ttl))
+            .await
+            .map_err(|e| UpdateError::Other(anyhow::anyhow!(e.to_string())))?;
+
+        Ok(session_key)
+    }
+
+    async fn update_ttl(
+        &self,
+        session_key: &SessionKey,
+        ttl: &CookieDuration,
+    ) -> Result<(), anyhow::Error> {
+        self.cache
+            .expire_auth_session(session_key.as_ref(), to_std_duration(ttl))
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+
+    async fn delete(&self, session_key: &SessionKey) -> Result<(), anyhow::Error> {
+        self.cache
+            .delete_auth_session(session_key.as_ref())
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+    }
+}
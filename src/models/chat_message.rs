@@ -0,0 +1,33 @@
+use chrono::NaiveDateTime;
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+
+/// Longest chat message in chars
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 500;
+
+#[derive(Queryable, Selectable, Debug, Identifiable, Serialize, Clone)]
+#[diesel(table_name = crate::schema::chat_messages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ChatMessage {
+    pub id: i64,
+    pub session_id: String,
+    pub user_id: String,
+    pub message: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable, Debug, Deserialize)]
+#[diesel(table_name = crate::schema::chat_messages)]
+pub struct NewChatMessage {
+    pub session_id: String,
+    pub user_id: String,
+    pub message: String,
+}
+
+/// Page of the chat history, oldest message first
+#[derive(Debug, Serialize)]
+pub struct ChatPage {
+    pub messages: Vec<ChatMessage>,
+    /// pass as `before` to load the older messages, `None` if there are none
+    pub next_before: Option<i64>,
+}
@@ -1,15 +1,17 @@
 use actix_session::{SessionGetError, SessionInsertError};
 use actix_web::{
     error::{BlockingError, ResponseError},
-    http::StatusCode,
+    http::{header, StatusCode},
 };
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use redis::RedisError;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::i18n;
+
 pub type Result<T, E = ApiError> = std::result::Result<T, E>;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ApiError {
     Internal {
@@ -41,6 +43,10 @@ pub enum ApiError {
         code: String,
         field: Option<String>,
     },
+    TooManyRequests {
+        message: String,
+        retry_after: u64,
+    },
 }
 
 impl std::fmt::Display for ApiError {
@@ -83,6 +89,9 @@ impl std::fmt::Display for ApiError {
                     message
                 )
             }
+            ApiError::TooManyRequests { message, .. } => {
+                write!(f, "Too many requests: {} - {}", self.status_code(), message)
+            }
         }
     }
 }
@@ -163,6 +172,22 @@ impl From<serde_json::Error> for ApiError {
     }
 }
 
+impl From<rmp_serde::encode::Error> for ApiError {
+    fn from(error: rmp_serde::encode::Error) -> ApiError {
+        ApiError::Internal {
+            message: format!("MessagePack error: {}", error),
+        }
+    }
+}
+
+impl From<rmp_serde::decode::Error> for ApiError {
+    fn from(error: rmp_serde::decode::Error) -> ApiError {
+        ApiError::Internal {
+            message: format!("MessagePack error: {}", error),
+        }
+    }
+}
+
 impl From<validator::ValidationErrors> for ApiError {
     fn from(error: validator::ValidationErrors) -> ApiError {
         ApiError::BadRequest {
@@ -173,7 +198,13 @@ impl From<validator::ValidationErrors> for ApiError {
 
 impl ResponseError for ApiError {
     fn error_response(&self) -> actix_web::HttpResponse {
-        actix_web::HttpResponse::build(self.status_code()).json(self)
+        let mut response = actix_web::HttpResponse::build(self.status_code());
+
+        if let ApiError::TooManyRequests { retry_after, .. } = self {
+            response.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        }
+
+        response.json(self)
     }
 }
 
@@ -189,10 +220,33 @@ impl ApiError {
             ApiError::NotNullViolation { .. } => StatusCode::BAD_REQUEST,
             ApiError::NotFound { .. } => StatusCode::NOT_FOUND,
             ApiError::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            ApiError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 
     pub fn internal(message: String) -> ApiError {
         ApiError::Internal { message }
     }
+
+    fn message_mut(&mut self) -> &mut String {
+        match self {
+            ApiError::Internal { message }
+            | ApiError::BadRequest { message }
+            | ApiError::NotFound { message }
+            | ApiError::Unauthorized { message }
+            | ApiError::UniqueViolation { message }
+            | ApiError::Validation { message, .. }
+            | ApiError::CheckViolation { message }
+            | ApiError::ForeignKeyViolation { message }
+            | ApiError::NotNullViolation { message }
+            | ApiError::TooManyRequests { message, .. } => message,
+        }
+    }
+
+    /// Translates the message using the catalog of the language
+    pub fn localize(&mut self, language: &str) {
+        let message = self.message_mut();
+
+        *message = i18n::translate(language, message);
+    }
 }
@@ -0,0 +1,11 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// One-time code that logs another device in as the same user
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeviceLink {
+    pub code: String,
+    pub user_id: String,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
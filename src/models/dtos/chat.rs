@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct ChatHistoryQuery {
+    /// id of the oldest message already loaded
+    pub before: Option<i64>,
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct RedeemDeviceLinkDto {
+    #[validate(length(min = 1, max = 20, message = "Wrong code length"))]
+    pub code: String,
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct MatchmakingDto {
+    /// defaults to english
+    #[validate(custom = "crate::models::dtos::session::validate_language")]
+    pub language: Option<String>,
+    #[validate(custom = "crate::models::dtos::user::validate_nickname")]
+    pub nickname: Option<String>,
+}
@@ -1,3 +1,8 @@
+pub mod chat;
+pub mod device_link;
+pub mod matchmaking;
 pub mod session;
+pub mod token;
 pub mod user;
 pub mod word;
+pub mod word_pack;
@@ -1,33 +1,98 @@
 use std::borrow::Cow;
 
+use chrono::NaiveDateTime;
 use serde::Deserialize;
 use validator::{Validate, ValidationError};
 
-use crate::utils::validators::valid_alphanumeric_name;
+use crate::{
+    models::session::{SessionPhase, SessionSort},
+    utils::{moderation::Strictness, session_ids::invalid_session_id, validators::valid_locale},
+};
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct NewSessionDto {
-    #[validate(
-        length(min = 1, max = 30, message = "Wrong id length", code = "wrong_length"),
-        custom = "validate_session_id"
-    )]
-    pub id: String,
+    /// a memorable id is generated if it is left out
+    #[validate(custom = "validate_session_id")]
+    pub id: Option<String>,
     #[serde(default)]
     pub public: Option<bool>,
+    pub content_filter: Option<Strictness>,
+    #[validate(custom = "validate_language")]
+    pub language: Option<String>,
+    #[validate(range(min = 2, max = 100, message = "Max players must be between 2 and 100"))]
+    pub max_players: Option<i32>,
 }
 
-fn validate_session_id(session_id: &str) -> Result<(), ValidationError> {
-    let session_id = session_id.trim();
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateSessionDto {
+    pub public: Option<bool>,
+    pub content_filter: Option<Strictness>,
+    #[validate(custom = "validate_language")]
+    pub language: Option<String>,
+    /// raising the limit promotes waitlisted users
+    #[validate(range(min = 2, max = 100, message = "Max players must be between 2 and 100"))]
+    pub max_players: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct JoinSessionDto {
+    #[validate(custom = "crate::models::dtos::user::validate_nickname")]
+    pub nickname: Option<String>,
+}
 
-    let validation_error = |msg: &str, code: &str| ValidationError {
-        message: Some(Cow::from(msg.to_owned())),
-        code: Cow::from(code.to_owned()),
-        params: std::collections::HashMap::new(),
-    };
+#[derive(Debug, Deserialize, Validate)]
+pub struct RematchDto {
+    /// copies the words instead of starting without any
+    #[serde(default)]
+    pub keep_words: bool,
+}
 
-    if let Some((msg, code)) = valid_alphanumeric_name(session_id, "Session id") {
-        return Err(validation_error(&msg, &code));
+#[derive(Debug, Deserialize, Validate)]
+pub struct NicknameDto {
+    /// `null` removes the nickname
+    #[validate(custom = "crate::models::dtos::user::validate_nickname")]
+    pub nickname: Option<String>,
+}
+
+pub(crate) fn validate_language(language: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_locale(language, "Language") {
+        return Err(ValidationError {
+            message: Some(Cow::from(msg)),
+            code: Cow::from(code),
+            params: std::collections::HashMap::new(),
+        });
     }
 
     Ok(())
 }
+
+fn validate_session_id(session_id: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = invalid_session_id(session_id) {
+        return Err(ValidationError {
+            message: Some(Cow::from(msg)),
+            code: Cow::from(code),
+            params: std::collections::HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct SessionsQuery {
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    #[validate(range(min = 1, max = 100, message = "Limit must be between 1 and 100"))]
+    pub limit: Option<i64>,
+    pub phase: Option<SessionPhase>,
+    #[validate(custom = "validate_language")]
+    pub language: Option<String>,
+    /// only sessions that can still be joined
+    pub free_slots: Option<bool>,
+    pub created_since: Option<NaiveDateTime>,
+    /// start of the session id
+    #[validate(length(max = 20, message = "Search must be at most 20 characters"))]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub sort: SessionSort,
+}
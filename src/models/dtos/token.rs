@@ -0,0 +1,20 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "grant_type", rename_all = "snake_case")]
+pub enum TokenRequestDto {
+    /// exchanges the current cookie session
+    Session,
+    Password {
+        login: String,
+        password: String,
+    },
+    RefreshToken {
+        refresh_token: String,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LogoutDto {
+    pub refresh_token: Option<String>,
+}
@@ -3,27 +3,123 @@ use std::borrow::Cow;
 use serde::Deserialize;
 use validator::{Validate, ValidationError};
 
-use crate::utils::validators::valid_alphanumeric_name;
+use crate::utils::validators::{
+    valid_content, valid_display_name, valid_display_name_format, valid_emoji, valid_hex_color,
+    valid_locale, valid_username,
+};
 
 #[derive(Deserialize, Validate)]
 pub struct NewUserDto {
-    #[validate(
-        length(min = 5, max = 20, message = "Wrong name length"),
-        custom = "validate_username"
-    )]
+    #[validate(custom = "validate_display_name")]
     pub name: Option<String>,
 }
 
-fn validate_username(session_id: &str) -> Result<(), ValidationError> {
-    let session_id = session_id.trim();
+#[derive(Deserialize, Validate)]
+pub struct UpdateProfileDto {
+    #[validate(custom = "validate_display_name")]
+    pub name: Option<String>,
+    #[validate(custom = "validate_avatar_color")]
+    pub avatar_color: Option<String>,
+    #[validate(custom = "validate_avatar_emoji")]
+    pub avatar_emoji: Option<String>,
+    #[validate(custom = "validate_locale")]
+    pub locale: Option<String>,
+}
 
-    let validation_error = |msg: &str, code: &str| ValidationError {
+#[derive(Deserialize, Validate)]
+#[validate(schema(function = "validate_register_login", skip_on_field_errors = false))]
+pub struct RegisterDto {
+    #[validate(email(message = "Invalid email", code = "wrong_format"))]
+    pub email: Option<String>,
+    #[validate(custom = "validate_username")]
+    pub username: Option<String>,
+    #[validate(length(
+        min = 8,
+        max = 128,
+        message = "Password must be between 8 and 128 characters",
+        code = "wrong_length"
+    ))]
+    pub password: String,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct LoginDto {
+    /// email or username
+    #[validate(length(min = 1, message = "Email or username is required"))]
+    pub login: String,
+    #[validate(length(min = 1, message = "Password is required"))]
+    pub password: String,
+}
+
+fn validate_register_login(register: &RegisterDto) -> Result<(), ValidationError> {
+    if register.email.is_none() && register.username.is_none() {
+        return Err(ValidationError {
+            message: Some(Cow::from("Email or username is required")),
+            code: Cow::from("missing_login"),
+            params: std::collections::HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let username = username.trim();
+
+    if let Some((msg, code)) = valid_username(username, "Username") {
+        return Err(validation_error(&msg, &code));
+    }
+
+    if let Some((msg, code)) = valid_content(username, "Username") {
+        return Err(validation_error(&msg, &code));
+    }
+
+    Ok(())
+}
+
+fn validation_error(msg: &str, code: &str) -> ValidationError {
+    ValidationError {
         message: Some(Cow::from(msg.to_owned())),
         code: Cow::from(code.to_owned()),
         params: std::collections::HashMap::new(),
-    };
+    }
+}
+
+pub(crate) fn validate_display_name(name: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_display_name(name, "Name") {
+        return Err(validation_error(&msg, &code));
+    }
+
+    Ok(())
+}
+
+/// The content filter of the session is applied when the nickname is stored
+pub(crate) fn validate_nickname(name: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_display_name_format(name, "Name") {
+        return Err(validation_error(&msg, &code));
+    }
+
+    Ok(())
+}
+
+fn validate_avatar_color(color: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_hex_color(color, "Avatar color") {
+        return Err(validation_error(&msg, &code));
+    }
+
+    Ok(())
+}
+
+fn validate_avatar_emoji(emoji: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_emoji(emoji, "Avatar emoji") {
+        return Err(validation_error(&msg, &code));
+    }
+
+    Ok(())
+}
 
-    if let Some((msg, code)) = valid_alphanumeric_name(session_id, "Username") {
+fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_locale(locale, "Locale") {
         return Err(validation_error(&msg, &code));
     }
 
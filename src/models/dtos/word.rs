@@ -1,8 +1,24 @@
+use std::borrow::Cow;
+
 use serde::Deserialize;
-use validator::Validate;
+use validator::{Validate, ValidationError};
+
+use crate::utils::validators::valid_word;
 
 #[derive(Deserialize, Debug, Validate)]
 pub struct NewWordDto {
-    #[validate(length(min = 3, max = 30, message = "Wrong word length"))]
+    #[validate(custom = "validate_word")]
     pub word: String,
 }
+
+fn validate_word(word: &str) -> Result<(), ValidationError> {
+    if let Some((msg, code)) = valid_word(word, "Word") {
+        return Err(ValidationError {
+            message: Some(Cow::from(msg)),
+            code: Cow::from(code),
+            params: std::collections::HashMap::new(),
+        });
+    }
+
+    Ok(())
+}
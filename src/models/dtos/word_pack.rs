@@ -0,0 +1,13 @@
+use serde::Deserialize;
+use validator::Validate;
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct SessionWordPacksDto {
+    #[validate(length(max = 20, message = "At most 20 word packs can be selected"))]
+    pub pack_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WordPacksQuery {
+    pub language: Option<String>,
+}
@@ -0,0 +1,162 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::game_result::{GamePlayer, GameWord};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GuessedWord {
+    pub word: String,
+    pub guessed_by: String,
+    pub performed_by: String,
+    /// time from handing out the word until it was guessed
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+/// State of a running game, kept in the cache while the session is played
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GameState {
+    pub id: Uuid,
+    /// increased with every guessed or skipped word
+    pub round: u32,
+    pub performer_id: String,
+    /// `None` once all words have been guessed
+    pub current_word: Option<String>,
+    pub remaining_words: Vec<String>,
+    pub guessed_words: Vec<GuessedWord>,
+    pub scores: HashMap<String, u32>,
+    /// how often each word was skipped
+    #[serde(default)]
+    pub skips: HashMap<String, u32>,
+    /// unix time in milliseconds
+    #[serde(default)]
+    pub started_at: i64,
+    /// when the current word was handed to the performer, unix time in milliseconds
+    #[serde(default)]
+    pub round_started_at: i64,
+}
+
+impl GameState {
+    pub fn new(performer_id: &str, mut words: Vec<String>) -> Self {
+        let current_word = words.pop();
+        let now = Utc::now().timestamp_millis();
+
+        Self {
+            id: Uuid::new_v4(),
+            round: 0,
+            performer_id: performer_id.to_string(),
+            current_word,
+            remaining_words: words,
+            guessed_words: vec![],
+            scores: HashMap::new(),
+            skips: HashMap::new(),
+            started_at: now,
+            round_started_at: now,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_word.is_none()
+    }
+
+    /// Scores the guesser and the performer, the guesser performs the next word
+    pub fn word_guessed(&mut self, guesser_id: &str) {
+        let Some(word) = self.current_word.take() else {
+            return;
+        };
+
+        *self.scores.entry(guesser_id.to_string()).or_default() += 1;
+        *self.scores.entry(self.performer_id.clone()).or_default() += 1;
+
+        let now = Utc::now().timestamp_millis();
+
+        self.guessed_words.push(GuessedWord {
+            word,
+            guessed_by: guesser_id.to_string(),
+            performed_by: self.performer_id.clone(),
+            duration_ms: (now - self.round_started_at).max(0) as u64,
+        });
+
+        self.round += 1;
+        self.round_started_at = now;
+        self.performer_id = guesser_id.to_string();
+        self.current_word = self.remaining_words.pop();
+    }
+
+    /// Puts the current word back at the end of the remaining words, the performer gets the next one
+    pub fn word_skipped(&mut self) {
+        let Some(word) = self.current_word.take() else {
+            return;
+        };
+
+        *self.skips.entry(word.clone()).or_default() += 1;
+
+        self.remaining_words.insert(0, word);
+
+        self.round += 1;
+        self.round_started_at = Utc::now().timestamp_millis();
+        self.current_word = self.remaining_words.pop();
+    }
+
+    /// Results of the finished game for the members and everyone who took part in it.
+    /// `guesses` are the number of guesses per user, correct or not.
+    pub fn player_results(
+        &self,
+        member_ids: &[String],
+        guesses: &HashMap<String, u32>,
+    ) -> Vec<GamePlayer> {
+        let best_score = self.scores.values().copied().max().unwrap_or_default();
+
+        let player_ids: BTreeSet<&String> = member_ids
+            .iter()
+            .chain(self.scores.keys())
+            .chain(guesses.keys())
+            .collect();
+
+        player_ids
+            .into_iter()
+            .map(|user_id| {
+                let performed = self
+                    .guessed_words
+                    .iter()
+                    .filter(|w| &w.performed_by == user_id);
+                let score = self.scores.get(user_id).copied().unwrap_or_default();
+
+                GamePlayer {
+                    game_id: self.id,
+                    user_id: user_id.clone(),
+                    score: score as i32,
+                    won: best_score > 0 && score == best_score,
+                    words_performed: performed.clone().count() as i32,
+                    words_guessed: self
+                        .guessed_words
+                        .iter()
+                        .filter(|w| &w.guessed_by == user_id)
+                        .count() as i32,
+                    guesses: guesses.get(user_id).copied().unwrap_or_default() as i32,
+                    perform_time_ms: performed.map(|w| w.duration_ms as i64).sum(),
+                }
+            })
+            .collect()
+    }
+
+    /// Guessed words in the order they were guessed, as stored for the summary
+    pub fn game_words(&self) -> Vec<GameWord> {
+        self.guessed_words
+            .iter()
+            .enumerate()
+            .map(|(position, w)| GameWord {
+                game_id: self.id,
+                position: position as i32,
+                word: w.word.clone(),
+                performed_by: Some(w.performed_by.clone()),
+                guessed_by: Some(w.guessed_by.clone()),
+                duration_ms: w.duration_ms as i64,
+                skips: self.skips.get(&w.word).copied().unwrap_or_default() as i32,
+            })
+            .collect()
+    }
+}
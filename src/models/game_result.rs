@@ -0,0 +1,176 @@
+use chrono::NaiveDateTime;
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Finished game, stored once the last word was guessed
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::schema::games)]
+pub struct NewGame {
+    pub id: Uuid,
+    pub session_id: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+}
+
+/// Result of one player in a finished game
+#[derive(Queryable, Selectable, Insertable, Debug, Serialize, Clone)]
+#[diesel(table_name = crate::schema::game_players)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct GamePlayer {
+    pub game_id: Uuid,
+    pub user_id: String,
+    pub score: i32,
+    /// had the highest score, ties all win
+    pub won: bool,
+    /// words guessed by others while the player was performing
+    pub words_performed: i32,
+    /// correct guesses of the player
+    pub words_guessed: i32,
+    /// all guesses of the player, correct or not
+    pub guesses: i32,
+    /// time the others needed to guess the words the player performed
+    pub perform_time_ms: i64,
+}
+
+/// Word of a finished game, in the order the words were guessed
+#[derive(Queryable, Selectable, Insertable, Debug, Serialize, Clone)]
+#[diesel(table_name = crate::schema::game_words)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct GameWord {
+    #[serde(skip)]
+    pub game_id: Uuid,
+    pub position: i32,
+    pub word: String,
+    /// `None` if the user deleted their account
+    pub performed_by: Option<String>,
+    pub guessed_by: Option<String>,
+    pub duration_ms: i64,
+    /// times the performers skipped the word before it was guessed
+    pub skips: i32,
+}
+
+/// Finished game as stored
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::schema::games)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Game {
+    pub id: Uuid,
+    pub session_id: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+}
+
+/// Recap of a finished game, sent when it finishes and kept for the session
+#[derive(Debug, Serialize, Clone)]
+pub struct GameSummary {
+    pub game_id: Uuid,
+    pub session_id: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: NaiveDateTime,
+    /// sorted by score, the final scores
+    pub players: Vec<GamePlayer>,
+    /// in the order they were guessed, there are no teams so every word names its performer and guesser
+    pub words: Vec<GameWord>,
+    pub fastest_guess: Option<GameWord>,
+    /// `None` if no word was skipped, ties go to the word guessed first
+    pub most_skipped_word: Option<GameWord>,
+    /// performer whose words got guessed the most, ties go to the faster one
+    pub mvp: Option<String>,
+}
+
+impl From<NewGame> for Game {
+    fn from(game: NewGame) -> Self {
+        Self {
+            id: game.id,
+            session_id: game.session_id,
+            started_at: game.started_at,
+            finished_at: game.finished_at,
+        }
+    }
+}
+
+impl GameSummary {
+    pub fn new(game: Game, mut players: Vec<GamePlayer>, mut words: Vec<GameWord>) -> Self {
+        players.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.user_id.cmp(&b.user_id))
+        });
+        words.sort_by_key(|w| w.position);
+
+        let fastest_guess = words.iter().min_by_key(|w| w.duration_ms).cloned();
+
+        let most_skipped_word = words
+            .iter()
+            .filter(|w| w.skips > 0)
+            .rev()
+            .max_by_key(|w| w.skips)
+            .cloned();
+
+        let mvp = players
+            .iter()
+            .filter(|p| p.words_performed > 0)
+            .max_by(|a, b| {
+                a.words_performed
+                    .cmp(&b.words_performed)
+                    .then_with(|| b.perform_time_ms.cmp(&a.perform_time_ms))
+            })
+            .map(|p| p.user_id.clone());
+
+        Self {
+            game_id: game.id,
+            session_id: game.session_id,
+            started_at: game.started_at,
+            finished_at: game.finished_at,
+            players,
+            words,
+            fastest_guess,
+            most_skipped_word,
+            mvp,
+        }
+    }
+}
+
+/// Totals of a user across all finished games
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserStats {
+    pub user_id: String,
+    pub games_played: u32,
+    pub wins: u32,
+    pub words_guessed_as_performer: u32,
+    pub correct_guesses: u32,
+    pub guesses: u32,
+    /// share of the guesses that were correct, 0 without guesses
+    pub guess_rate: f64,
+    /// how long it took to get a performed word guessed, `None` without performed words
+    pub average_time_per_word_ms: Option<u64>,
+}
+
+impl UserStats {
+    pub fn from_results(user_id: &str, results: &[GamePlayer]) -> Self {
+        let sum = |value: fn(&GamePlayer) -> i64| -> i64 { results.iter().map(value).sum() };
+
+        let words_performed = sum(|r| r.words_performed.into());
+        let correct_guesses = sum(|r| r.words_guessed.into());
+        let guesses = sum(|r| r.guesses.into());
+        let perform_time_ms = sum(|r| r.perform_time_ms);
+
+        Self {
+            user_id: user_id.to_string(),
+            games_played: results.len() as u32,
+            wins: results.iter().filter(|r| r.won).count() as u32,
+            words_guessed_as_performer: words_performed as u32,
+            correct_guesses: correct_guesses as u32,
+            guesses: guesses as u32,
+            guess_rate: match guesses {
+                0 => 0.0,
+                _ => correct_guesses as f64 / guesses as f64,
+            },
+            average_time_per_word_ms: match words_performed {
+                0 => None,
+                _ => Some((perform_time_ms / words_performed) as u64),
+            },
+        }
+    }
+}
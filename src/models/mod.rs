@@ -1,6 +1,12 @@
+pub mod chat_message;
 pub mod custom_api_errors;
+pub mod device_link;
 pub mod dtos;
+pub mod game;
+pub mod game_result;
 pub mod session;
+pub mod token;
 pub mod user;
 pub mod users_sessions;
 pub mod word;
+pub mod word_pack;
@@ -1,8 +1,14 @@
+use std::{fmt, str::FromStr};
+
 use super::{user::User, word::Word};
+use crate::utils::moderation::Strictness;
 use chrono::NaiveDateTime;
-use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
 
+/// Language of sessions created without one
+pub const DEFAULT_LANGUAGE: &str = "en";
+
 #[derive(Queryable, Selectable, Debug, Identifiable, Serialize, Clone)]
 #[diesel(table_name = crate::schema::sessions)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -11,14 +17,122 @@ pub struct Session {
     pub public: bool,
     pub created_at: NaiveDateTime,
     pub admin_user_id: String,
+    pub content_filter: String,
+    pub language: String,
+    pub phase: String,
+    pub last_activity_at: NaiveDateTime,
+    /// further users are put on the waitlist
+    pub max_players: i32,
+}
+
+impl Session {
+    pub fn content_filter(&self) -> Strictness {
+        self.content_filter.parse().unwrap_or_default()
+    }
+
+    pub fn phase(&self) -> SessionPhase {
+        self.phase.parse().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionPhase {
+    /// players are joining and adding words
+    #[default]
+    Lobby,
+    Playing,
+    Finished,
 }
 
-#[derive(Insertable, Debug, Deserialize)]
+impl fmt::Display for SessionPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SessionPhase::Lobby => "lobby",
+            SessionPhase::Playing => "playing",
+            SessionPhase::Finished => "finished",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for SessionPhase {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "lobby" => Ok(SessionPhase::Lobby),
+            "playing" => Ok(SessionPhase::Playing),
+            "finished" => Ok(SessionPhase::Finished),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Insertable, Debug, Deserialize, Clone)]
 #[diesel(table_name = crate::schema::sessions)]
 pub struct NewSession {
     pub id: String,
     pub public: bool,
     pub admin_user_id: String,
+    pub content_filter: String,
+    pub language: String,
+    pub max_players: i32,
+}
+
+/// Settings the admin can change, `None` fields are left as they are
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = crate::schema::sessions)]
+pub struct SessionUpdate {
+    pub public: Option<bool>,
+    pub content_filter: Option<String>,
+    pub language: Option<String>,
+    pub max_players: Option<i32>,
+}
+
+impl SessionUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.public.is_none()
+            && self.content_filter.is_none()
+            && self.language.is_none()
+            && self.max_players.is_none()
+    }
+}
+
+/// Outcome of joining, users joining a full session are put on its waitlist
+#[derive(Debug)]
+pub enum SessionJoin {
+    Joined(Session),
+    Waitlisted {
+        session: Session,
+        /// 1 is next in line
+        position: i64,
+    },
+}
+
+/// Session found or created by quick play
+#[derive(Debug, Serialize)]
+pub struct SessionMatch {
+    pub session: Session,
+    /// true if no open lobby was found and the user is the admin of a new session
+    pub created: bool,
+    /// set if the user already queued for the lobby before and is still waiting for a slot
+    pub waitlist_position: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionIdAvailability {
+    pub id: String,
+    pub available: bool,
+    /// why the id can't be used, if it is invalid
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionWaitlisted {
+    pub session: Session,
+    pub waitlist_position: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,3 +150,93 @@ pub struct SessionInfoPersonal {
     pub users: Vec<User>,
     pub number_of_words: u32,
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionSort {
+    /// newest first
+    #[default]
+    Created,
+    /// most members first
+    Players,
+    /// most recently active first
+    Activity,
+}
+
+/// Position in a session listing, the sort key and id of the last listed session.
+/// Sent to clients as `<key>:<id>`, timestamps as microseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionCursor {
+    pub key: i64,
+    pub id: String,
+}
+
+impl fmt::Display for SessionCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.key, self.id)
+    }
+}
+
+impl FromStr for SessionCursor {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (key, id) = value.split_once(':').ok_or(())?;
+
+        if id.is_empty() {
+            return Err(());
+        }
+
+        Ok(Self {
+            key: key.parse().map_err(|_| ())?,
+            id: id.to_string(),
+        })
+    }
+}
+
+/// Filters of the public session listing, `None` filters match every session
+#[derive(Debug, Default)]
+pub struct SessionFilter {
+    pub phase: Option<SessionPhase>,
+    pub language: Option<String>,
+    /// only sessions with fewer members than their `max_players`
+    pub free_slots: bool,
+    pub created_since: Option<NaiveDateTime>,
+    /// only sessions with activity since then, like joins, words or chat messages
+    pub active_since: Option<NaiveDateTime>,
+    pub id_prefix: Option<String>,
+    pub sort: SessionSort,
+    pub cursor: Option<SessionCursor>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionListing {
+    #[serde(flatten)]
+    pub session: Session,
+    pub number_of_players: i64,
+    /// players currently connected to the websocket
+    pub online_players: usize,
+}
+
+impl SessionListing {
+    pub fn cursor(&self, sort: SessionSort) -> SessionCursor {
+        let key = match sort {
+            SessionSort::Created => self.session.created_at.timestamp_micros(),
+            SessionSort::Players => self.number_of_players,
+            SessionSort::Activity => self.session.last_activity_at.timestamp_micros(),
+        };
+
+        SessionCursor {
+            key,
+            id: self.session.id.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionPage {
+    pub sessions: Vec<SessionListing>,
+    /// pass as `cursor` to load the next page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub token_type: String,
+    /// seconds until the access token expires
+    pub expires_in: i64,
+    pub refresh_token: String,
+}
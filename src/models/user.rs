@@ -1,8 +1,8 @@
 use chrono::NaiveDateTime;
-use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
 
-use super::session::Session;
+use super::{chat_message::ChatMessage, game_result::GamePlayer, session::Session, word::Word};
 
 #[derive(Queryable, Selectable, Debug, Clone, Identifiable, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::users)]
@@ -11,6 +11,9 @@ pub struct User {
     pub id: String,
     pub name: Option<String>,
     pub created_at: NaiveDateTime,
+    pub avatar_color: Option<String>,
+    pub avatar_emoji: Option<String>,
+    pub locale: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -20,8 +23,69 @@ pub struct NewUser {
     pub name: Option<String>,
 }
 
+/// The user as seen by themselves, including the private login fields
+#[derive(Queryable, Selectable, Debug, Serialize)]
+#[diesel(table_name = crate::schema::users)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Account {
+    pub id: String,
+    pub name: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub avatar_color: Option<String>,
+    pub avatar_emoji: Option<String>,
+    pub locale: Option<String>,
+    pub email: Option<String>,
+    pub username: Option<String>,
+}
+
+/// Profile fields to change, fields that are `None` are left as they are
+#[derive(AsChangeset, Debug, Default)]
+#[diesel(table_name = crate::schema::users)]
+pub struct ProfileUpdate {
+    pub name: Option<String>,
+    pub avatar_color: Option<String>,
+    pub avatar_emoji: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl ProfileUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.avatar_color.is_none()
+            && self.avatar_emoji.is_none()
+            && self.locale.is_none()
+    }
+}
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = crate::schema::users)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Credentials {
+    pub id: String,
+    pub password_hash: Option<String>,
+}
+
+#[derive(AsChangeset, Debug)]
+#[diesel(table_name = crate::schema::users)]
+pub struct NewCredentials {
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub password_hash: String,
+}
+
 #[derive(Serialize)]
 pub struct FullUserInfo {
     pub user: User,
     pub sessions: Vec<Session>,
 }
+
+/// Everything stored about a user, for data export requests
+#[derive(Serialize)]
+pub struct UserDataExport {
+    pub exported_at: NaiveDateTime,
+    pub account: Account,
+    pub sessions: Vec<Session>,
+    pub words: Vec<Word>,
+    pub chat_messages: Vec<ChatMessage>,
+    pub games: Vec<GamePlayer>,
+}
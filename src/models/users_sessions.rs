@@ -1,8 +1,6 @@
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
 
-use super::user::User;
-
 #[derive(Queryable, Selectable, Debug, Identifiable, Serialize, Deserialize, Insertable)]
 #[diesel(table_name = crate::schema::users_sessions)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -10,10 +8,15 @@ use super::user::User;
 pub struct UsersSession {
     pub user_id: String,
     pub session_id: String,
+    /// overrides the user's name within this session
+    pub nickname: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SessionUser {
-    pub user: User,
-    pub number_of_words: u16,
+/// User waiting for a free slot in a full session, promoted in the order they joined
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::schema::session_waitlist)]
+pub struct WaitlistEntry {
+    pub session_id: String,
+    pub user_id: String,
+    pub nickname: Option<String>,
 }
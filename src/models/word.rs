@@ -2,6 +2,11 @@ use chrono::NaiveDateTime;
 use diesel::{Identifiable, Insertable, Queryable, Selectable};
 use serde::{Deserialize, Serialize};
 
+use crate::utils::{
+    guesses::normalize_guess,
+    validators::{normalize_display_name, normalize_word_key},
+};
+
 #[derive(Queryable, Selectable, Debug, Identifiable, Serialize)]
 #[diesel(table_name = crate::schema::words)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -19,4 +24,20 @@ pub struct NewWord {
     pub word: String,
     pub session_id: String,
     pub user_id: String,
+    pub normalized: String,
+    pub similarity_key: String,
+}
+
+impl NewWord {
+    /// Normalizes the word, `normalized` is the key duplicates are detected by
+    /// and `similarity_key` the one near duplicates are detected by
+    pub fn new(word: &str, session_id: &str, user_id: &str) -> Self {
+        Self {
+            word: normalize_display_name(word),
+            session_id: session_id.to_string(),
+            user_id: user_id.to_string(),
+            normalized: normalize_word_key(word),
+            similarity_key: normalize_guess(word),
+        }
+    }
 }
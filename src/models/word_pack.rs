@@ -0,0 +1,57 @@
+use chrono::NaiveDateTime;
+use diesel::{AsChangeset, Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Selectable, Debug, Identifiable, Serialize, Clone)]
+#[diesel(table_name = crate::schema::word_packs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WordPack {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub created_at: NaiveDateTime,
+    pub language: String,
+}
+
+#[derive(Insertable, AsChangeset, Debug, Deserialize)]
+#[diesel(table_name = crate::schema::word_packs)]
+pub struct NewWordPack {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    #[serde(default = "default_language")]
+    pub language: String,
+}
+
+fn default_language() -> String {
+    "en".to_string()
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::schema::word_pack_words)]
+pub struct WordPackWord {
+    pub pack_id: String,
+    pub word: String,
+}
+
+#[derive(Insertable, Debug)]
+#[diesel(table_name = crate::schema::sessions_word_packs)]
+pub struct SessionWordPack {
+    pub session_id: String,
+    pub pack_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WordPackInfo {
+    #[serde(flatten)]
+    pub pack: WordPack,
+    pub number_of_words: i64,
+}
+
+/// Word pack as shipped in a json file of the word packs directory
+#[derive(Debug, Deserialize)]
+pub struct WordPackFile {
+    #[serde(flatten)]
+    pub pack: NewWordPack,
+    pub words: Vec<String>,
+}
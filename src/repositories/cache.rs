@@ -1,10 +1,55 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use redis::Client;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{models::custom_api_errors::Result, utils::envs};
 
+mod auth_sessions;
+mod device_links;
+mod games;
+mod matchmaking;
+mod refresh_tokens;
+mod stats;
+
+/// KEYS are the window logs, ARGV the current time in ms, the member to record
+/// and then the max hits and window in ms of every key.
+/// Returns 0 if the hit was recorded, otherwise the ms to wait.
+const SLIDING_WINDOWS_SCRIPT: &str = r"
+local now = tonumber(ARGV[1])
+local retry_after = 0
+
+for i, key in ipairs(KEYS) do
+    local max_hits = tonumber(ARGV[1 + i * 2])
+    local window = tonumber(ARGV[2 + i * 2])
+
+    redis.call('ZREMRANGEBYSCORE', key, 0, now - window)
+
+    if redis.call('ZCARD', key) >= max_hits then
+        local oldest = redis.call('ZRANGE', key, 0, 0, 'WITHSCORES')
+        local wait = window
+        if oldest[2] then
+            wait = tonumber(oldest[2]) + window - now
+        end
+        retry_after = math.max(retry_after, wait, 1)
+    end
+end
+
+if retry_after > 0 then
+    return retry_after
+end
+
+for i, key in ipairs(KEYS) do
+    redis.call('ZADD', key, now, ARGV[2])
+    redis.call('PEXPIRE', key, tonumber(ARGV[2 + i * 2]))
+end
+
+return 0
+";
+
 #[derive(Clone, Debug)]
 pub struct Cache {
     pub client: Arc<Client>,
@@ -29,7 +74,7 @@ impl Cache {
         redis::cmd("SET")
             .arg(key)
             .arg(value)
-            .query_async(&mut self.connection().await?)
+            .query_async::<_, ()>(&mut self.connection().await?)
             .await?;
 
         Ok(())
@@ -50,7 +95,7 @@ impl Cache {
         redis::cmd("JSON.SET")
             .arg(key)
             .arg(value)
-            .query_async(&mut self.connection().await?)
+            .query_async::<_, ()>(&mut self.connection().await?)
             .await?;
 
         Ok(())
@@ -71,7 +116,7 @@ impl Cache {
         redis::cmd("SADD")
             .arg(key)
             .arg(values)
-            .query_async(&mut self.connection().await?)
+            .query_async::<_, ()>(&mut self.connection().await?)
             .await?;
 
         Ok(())
@@ -81,12 +126,29 @@ impl Cache {
         redis::cmd("SREM")
             .arg(key)
             .arg(value)
-            .query_async(&mut self.connection().await?)
+            .query_async::<_, ()>(&mut self.connection().await?)
             .await?;
 
         Ok(())
     }
 
+    /// Returns false if the value was not in the source set
+    pub async fn move_string_between_sets(
+        &self,
+        source_key: &str,
+        destination_key: &str,
+        value: &str,
+    ) -> Result<bool> {
+        let moved: bool = redis::cmd("SMOVE")
+            .arg(source_key)
+            .arg(destination_key)
+            .arg(value)
+            .query_async(&mut self.connection().await?)
+            .await?;
+
+        Ok(moved)
+    }
+
     pub async fn get_string_set(&self, key: &str) -> Result<Vec<String>> {
         let values: Vec<String> = redis::cmd("SMEMBERS")
             .arg(key)
@@ -96,6 +158,23 @@ impl Cache {
         Ok(values)
     }
 
+    /// Number of members of each set, in the order of the keys
+    pub async fn count_set_members(&self, keys: Vec<String>) -> Result<Vec<usize>> {
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut pipe = redis::pipe();
+
+        for key in &keys {
+            pipe.cmd("SCARD").arg(key);
+        }
+
+        let counts: Vec<usize> = pipe.query_async(&mut self.connection().await?).await?;
+
+        Ok(counts)
+    }
+
     pub async fn key_exists(&self, key: &str) -> Result<bool> {
         let exists: bool = redis::cmd("EXISTS")
             .arg(key)
@@ -105,7 +184,125 @@ impl Cache {
         Ok(exists)
     }
 
+    /// Sets the key with a time to live, returns false if `only_if_absent` is set and the key exists
+    pub async fn set_with_expiry(
+        &self,
+        key: &str,
+        value: &str,
+        ttl: Duration,
+        only_if_absent: bool,
+    ) -> Result<bool> {
+        let mut cmd = redis::cmd("SET");
+
+        cmd.arg(key)
+            .arg(value)
+            .arg("PX")
+            .arg(ttl.as_millis() as u64);
+
+        if only_if_absent {
+            cmd.arg("NX");
+        }
+
+        let res: Option<String> = cmd.query_async(&mut self.connection().await?).await?;
+
+        Ok(res.is_some())
+    }
+
+    pub async fn get_optional(&self, key: &str) -> Result<Option<String>> {
+        let value: Option<String> = redis::cmd("GET")
+            .arg(key)
+            .query_async(&mut self.connection().await?)
+            .await?;
+
+        Ok(value)
+    }
+
+    /// Gets and deletes the key atomically, so the value can only be taken once
+    pub async fn take(&self, key: &str) -> Result<Option<String>> {
+        let value: Option<String> = redis::cmd("GETDEL")
+            .arg(key)
+            .query_async(&mut self.connection().await?)
+            .await?;
+
+        Ok(value)
+    }
+
+    pub async fn expire(&self, key: &str, ttl: Duration) -> Result<()> {
+        redis::cmd("PEXPIRE")
+            .arg(key)
+            .arg(ttl.as_millis() as u64)
+            .query_async::<_, ()>(&mut self.connection().await?)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        redis::cmd("DEL")
+            .arg(key)
+            .query_async::<_, ()>(&mut self.connection().await?)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes the key only if it still holds the value, e.g. to release a lock that might have expired
+    pub async fn delete_if_equals(&self, key: &str, value: &str) -> Result<()> {
+        redis::Script::new(
+            r"if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end",
+        )
+        .key(key)
+        .arg(value)
+        .invoke_async::<_, ()>(&mut self.connection().await?)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records a hit in every sliding window log and returns how long to wait if any limit is exceeded.
+    /// All windows are checked before the hit is recorded, so a rejected hit counts against none of them
+    /// and clients that keep retrying are not locked out forever.
+    pub async fn hit_sliding_windows(
+        &self,
+        windows: &[(String, u32, Duration)],
+    ) -> Result<Option<Duration>> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let member = format!("{}-{}", now, rand::random::<u32>());
+
+        let script = redis::Script::new(SLIDING_WINDOWS_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation.arg(now).arg(&member);
+
+        for (key, max_hits, window) in windows {
+            invocation
+                .key(key)
+                .arg(*max_hits)
+                .arg(window.as_millis() as u64);
+        }
+
+        let retry_after_ms: u64 = invocation
+            .invoke_async(&mut self.connection().await?)
+            .await?;
+
+        match retry_after_ms {
+            0 => Ok(None),
+            retry_after_ms => Ok(Some(Duration::from_millis(retry_after_ms))),
+        }
+    }
+
     pub fn session_users_key(session_id: &str) -> String {
         format!("session:{}:users", session_id)
     }
+
+    /// Connected users that are still on the waitlist, they get no broadcasts until they are promoted
+    pub fn session_waiting_users_key(session_id: &str) -> String {
+        format!("session:{}:waiting", session_id)
+    }
+
+    pub fn rate_limit_key(name: &str, client: &str) -> String {
+        format!("rate_limit:{}:{}", name, client)
+    }
 }
@@ -0,0 +1,117 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::{models::custom_api_errors::Result, routes::SESSION_USER_ID};
+
+use super::Cache;
+
+/// Session state as stored by actix-session, values are json encoded
+pub type AuthSessionState = HashMap<String, String>;
+
+fn state_user_id(state: &AuthSessionState) -> Option<String> {
+    state
+        .get(SESSION_USER_ID)
+        .and_then(|user_id| serde_json::from_str::<String>(user_id).ok())
+}
+
+impl Cache {
+    pub async fn get_auth_session(&self, session_key: &str) -> Result<Option<AuthSessionState>> {
+        let state = self
+            .get_optional(&Self::auth_session_key(session_key))
+            .await?;
+
+        match state {
+            Some(state) => Ok(Some(serde_json::from_str(&state)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the state and indexes the session under its user, so it can be logged out remotely
+    pub async fn set_auth_session(
+        &self,
+        session_key: &str,
+        state: &AuthSessionState,
+        ttl: Duration,
+    ) -> Result<()> {
+        self.set_with_expiry(
+            &Self::auth_session_key(session_key),
+            &serde_json::to_string(state)?,
+            ttl,
+            false,
+        )
+        .await?;
+
+        if let Some(user_id) = state_user_id(state) {
+            self.index_auth_session(&user_id, session_key, ttl).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Extends the session and its entry in the index of its user, so it can still be logged out remotely
+    pub async fn expire_auth_session(&self, session_key: &str, ttl: Duration) -> Result<()> {
+        let Some(state) = self.get_auth_session(session_key).await? else {
+            return Ok(());
+        };
+
+        self.expire(&Self::auth_session_key(session_key), ttl)
+            .await?;
+
+        if let Some(user_id) = state_user_id(&state) {
+            self.index_auth_session(&user_id, session_key, ttl).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn index_auth_session(
+        &self,
+        user_id: &str,
+        session_key: &str,
+        ttl: Duration,
+    ) -> Result<()> {
+        let index_key = Self::user_auth_sessions_key(user_id);
+
+        self.push_strings_to_set(&index_key, vec![session_key])
+            .await?;
+        self.expire(&index_key, ttl).await
+    }
+
+    pub async fn delete_auth_session(&self, session_key: &str) -> Result<()> {
+        let state = self.take(&Self::auth_session_key(session_key)).await?;
+
+        let user_id = state
+            .and_then(|state| serde_json::from_str::<AuthSessionState>(&state).ok())
+            .and_then(|state| state_user_id(&state));
+
+        if let Some(user_id) = user_id {
+            self.remove_string_from_set(&Self::user_auth_sessions_key(&user_id), session_key)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes every session that is still logged in as the user
+    pub async fn delete_user_auth_sessions(&self, user_id: &str) -> Result<()> {
+        let index_key = Self::user_auth_sessions_key(user_id);
+
+        for session_key in self.get_string_set(&index_key).await? {
+            let state = self.get_auth_session(&session_key).await?;
+
+            // the session might have switched to another user since it was indexed
+            if state.as_ref().and_then(state_user_id).as_deref() == Some(user_id) {
+                self.delete(&Self::auth_session_key(&session_key)).await?;
+            }
+        }
+
+        self.delete(&index_key).await
+    }
+
+    fn auth_session_key(session_key: &str) -> String {
+        format!("auth_session:{}", session_key)
+    }
+
+    fn user_auth_sessions_key(user_id: &str) -> String {
+        format!("user:{}:auth_sessions", user_id)
+    }
+}
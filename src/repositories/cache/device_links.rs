@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::models::{
+    custom_api_errors::{ApiError, Result},
+    device_link::DeviceLink,
+};
+
+use super::Cache;
+
+/// How long a device link code can be redeemed
+const DEVICE_LINK_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Without 0/O and 1/I, so codes can be typed in from another screen
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 8;
+
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..CODE_LENGTH)
+        .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+        .collect()
+}
+
+fn not_found() -> ApiError {
+    ApiError::NotFound {
+        message: "Device link not found or expired".to_string(),
+    }
+}
+
+impl Cache {
+    pub async fn create_device_link(&self, user_id: &str) -> Result<DeviceLink> {
+        let created_at = chrono::Utc::now().naive_utc();
+        let expires_at = created_at + chrono::Duration::seconds(DEVICE_LINK_TTL.as_secs() as i64);
+
+        // codes are short, so retry on the rare collision instead of overwriting another link
+        for _ in 0..5 {
+            let link = DeviceLink {
+                code: generate_code(),
+                user_id: user_id.to_string(),
+                created_at,
+                expires_at,
+            };
+
+            let created = self
+                .set_with_expiry(
+                    &Self::device_link_key(&link.code),
+                    &serde_json::to_string(&link)?,
+                    DEVICE_LINK_TTL,
+                    true,
+                )
+                .await?;
+
+            if created {
+                self.push_strings_to_set(&Self::user_device_links_key(user_id), vec![&link.code])
+                    .await?;
+
+                return Ok(link);
+            }
+        }
+
+        Err(ApiError::internal(
+            "Could not generate a unique device link code".to_string(),
+        ))
+    }
+
+    /// Active links of a user, expired codes are removed from the index on the way
+    pub async fn get_device_links(&self, user_id: &str) -> Result<Vec<DeviceLink>> {
+        let index_key = Self::user_device_links_key(user_id);
+
+        let mut links = vec![];
+
+        for code in self.get_string_set(&index_key).await? {
+            match self.get_optional(&Self::device_link_key(&code)).await? {
+                Some(link) => links.push(serde_json::from_str::<DeviceLink>(&link)?),
+                None => self.remove_string_from_set(&index_key, &code).await?,
+            }
+        }
+
+        links.sort_by_key(|link| link.created_at);
+
+        Ok(links)
+    }
+
+    pub async fn revoke_device_link(&self, user_id: &str, code: &str) -> Result<()> {
+        let code = Self::normalize_device_link_code(code);
+        let key = Self::device_link_key(&code);
+
+        let link = self.get_optional(&key).await?.ok_or_else(not_found)?;
+        let link = serde_json::from_str::<DeviceLink>(&link)?;
+
+        if link.user_id != user_id {
+            return Err(not_found());
+        }
+
+        self.delete(&key).await?;
+        self.remove_string_from_set(&Self::user_device_links_key(user_id), &code)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Consumes the code, so every link can only be redeemed once
+    pub async fn redeem_device_link(&self, code: &str) -> Result<DeviceLink> {
+        let code = Self::normalize_device_link_code(code);
+
+        let link = self
+            .take(&Self::device_link_key(&code))
+            .await?
+            .ok_or_else(not_found)?;
+        let link = serde_json::from_str::<DeviceLink>(&link)?;
+
+        self.remove_string_from_set(&Self::user_device_links_key(&link.user_id), &code)
+            .await?;
+
+        Ok(link)
+    }
+
+    /// Codes are accepted case insensitive and with separators, e.g. `abcd-efgh`
+    fn normalize_device_link_code(code: &str) -> String {
+        code.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    }
+
+    fn device_link_key(code: &str) -> String {
+        format!("device_link:{}", code)
+    }
+
+    fn user_device_links_key(user_id: &str) -> String {
+        format!("user:{}:device_links", user_id)
+    }
+}
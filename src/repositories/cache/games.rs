@@ -0,0 +1,81 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::models::{custom_api_errors::Result, game::GameState};
+
+use super::Cache;
+
+/// Games that are not played anymore are dropped after a day
+const GAME_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+impl Cache {
+    pub async fn get_game(&self, session_id: &str) -> Result<Option<GameState>> {
+        let game = self.get_optional(&Self::game_key(session_id)).await?;
+
+        match game {
+            Some(game) => Ok(Some(serde_json::from_str(&game)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set_game(&self, session_id: &str, game: &GameState) -> Result<()> {
+        self.set_with_expiry(
+            &Self::game_key(session_id),
+            &serde_json::to_string(game)?,
+            GAME_TTL,
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns true for the first caller only, so a round can't be finished twice by concurrent guesses
+    pub async fn claim_game_round(&self, game: &GameState, user_id: &str) -> Result<bool> {
+        self.set_with_expiry(
+            &format!("game:{}:round:{}", game.id, game.round),
+            user_id,
+            GAME_TTL,
+            true,
+        )
+        .await
+    }
+
+    /// Counts a guess of the user, correct or not, for the statistics
+    pub async fn count_guess(&self, game: &GameState, user_id: &str) -> Result<()> {
+        let key = Self::guesses_key(game);
+
+        redis::pipe()
+            .atomic()
+            .cmd("HINCRBY")
+            .arg(&key)
+            .arg(user_id)
+            .arg(1)
+            .ignore()
+            .cmd("PEXPIRE")
+            .arg(&key)
+            .arg(GAME_TTL.as_millis() as u64)
+            .ignore()
+            .query_async::<_, ()>(&mut self.connection().await?)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Number of guesses per user
+    pub async fn get_guess_counts(&self, game: &GameState) -> Result<HashMap<String, u32>> {
+        let counts: HashMap<String, u32> = redis::cmd("HGETALL")
+            .arg(Self::guesses_key(game))
+            .query_async(&mut self.connection().await?)
+            .await?;
+
+        Ok(counts)
+    }
+
+    fn guesses_key(game: &GameState) -> String {
+        format!("game:{}:guesses", game.id)
+    }
+
+    fn game_key(session_id: &str) -> String {
+        format!("session:{}:game", session_id)
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use crate::models::custom_api_errors::Result;
+
+use super::Cache;
+
+/// Upper bound for one matchmaking, the lock is released earlier when it is done
+const MATCHMAKING_LOCK_TTL: Duration = Duration::from_secs(5);
+
+/// Quick play keeps filling the lobby it created for this long
+const OPEN_LOBBY_TTL: Duration = Duration::from_secs(10 * 60);
+
+impl Cache {
+    /// Matchmaking of a language runs one at a time across all instances.
+    /// Returns the token to release the lock with, `None` if someone else holds it.
+    pub async fn lock_matchmaking(&self, language: &str) -> Result<Option<String>> {
+        let token = uuid::Uuid::new_v4().to_string();
+
+        let locked = self
+            .set_with_expiry(
+                &Self::matchmaking_lock_key(language),
+                &token,
+                MATCHMAKING_LOCK_TTL,
+                true,
+            )
+            .await?;
+
+        Ok(locked.then_some(token))
+    }
+
+    pub async fn unlock_matchmaking(&self, language: &str, token: &str) -> Result<()> {
+        self.delete_if_equals(&Self::matchmaking_lock_key(language), token)
+            .await
+    }
+
+    /// The lobby quick play is currently filling
+    pub async fn get_open_lobby(&self, language: &str) -> Result<Option<String>> {
+        self.get_optional(&Self::open_lobby_key(language)).await
+    }
+
+    pub async fn set_open_lobby(&self, language: &str, session_id: &str) -> Result<()> {
+        self.set_with_expiry(
+            &Self::open_lobby_key(language),
+            session_id,
+            OPEN_LOBBY_TTL,
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    fn matchmaking_lock_key(language: &str) -> String {
+        format!("matchmaking:{}:lock", language)
+    }
+
+    fn open_lobby_key(language: &str) -> String {
+        format!("matchmaking:{}:lobby", language)
+    }
+}
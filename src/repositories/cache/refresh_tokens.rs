@@ -0,0 +1,71 @@
+use std::time::Duration;
+
+use crate::{
+    models::custom_api_errors::{ApiError, Result},
+    utils::tokens::generate_refresh_token,
+};
+
+use super::Cache;
+
+/// How long a refresh token can be used, every refresh issues a new one
+const REFRESH_TOKEN_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+impl Cache {
+    pub async fn create_refresh_token(&self, user_id: &str) -> Result<String> {
+        let token = generate_refresh_token();
+
+        self.set_with_expiry(
+            &Self::refresh_token_key(&token),
+            user_id,
+            REFRESH_TOKEN_TTL,
+            false,
+        )
+        .await?;
+        self.push_strings_to_set(&Self::user_refresh_tokens_key(user_id), vec![&token])
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Invalidates the token and returns its user, so a refresh token can only be used once
+    pub async fn consume_refresh_token(&self, token: &str) -> Result<String> {
+        let user_id =
+            self.take(&Self::refresh_token_key(token))
+                .await?
+                .ok_or(ApiError::Unauthorized {
+                    message: "Invalid or expired refresh token".to_string(),
+                })?;
+
+        self.remove_string_from_set(&Self::user_refresh_tokens_key(&user_id), token)
+            .await?;
+
+        Ok(user_id)
+    }
+
+    pub async fn revoke_refresh_token(&self, token: &str) -> Result<()> {
+        if let Some(user_id) = self.take(&Self::refresh_token_key(token)).await? {
+            self.remove_string_from_set(&Self::user_refresh_tokens_key(&user_id), token)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn revoke_user_refresh_tokens(&self, user_id: &str) -> Result<()> {
+        let index_key = Self::user_refresh_tokens_key(user_id);
+
+        for token in self.get_string_set(&index_key).await? {
+            self.delete(&Self::refresh_token_key(&token)).await?;
+        }
+
+        self.delete(&index_key).await
+    }
+
+    fn refresh_token_key(token: &str) -> String {
+        format!("refresh_token:{}", token)
+    }
+
+    fn user_refresh_tokens_key(user_id: &str) -> String {
+        format!("user:{}:refresh_tokens", user_id)
+    }
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use crate::models::{custom_api_errors::Result, game_result::UserStats};
+
+use super::Cache;
+
+/// Stats are dropped when a game of the user finishes, this only bounds stale entries
+const USER_STATS_TTL: Duration = Duration::from_secs(60 * 60);
+
+impl Cache {
+    pub async fn get_user_stats(&self, user_id: &str) -> Result<Option<UserStats>> {
+        let stats = self.get_optional(&Self::user_stats_key(user_id)).await?;
+
+        match stats {
+            Some(stats) => Ok(Some(serde_json::from_str(&stats)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn set_user_stats(&self, stats: &UserStats) -> Result<()> {
+        self.set_with_expiry(
+            &Self::user_stats_key(&stats.user_id),
+            &serde_json::to_string(stats)?,
+            USER_STATS_TTL,
+            false,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn invalidate_user_stats(&self, user_id: &str) -> Result<()> {
+        self.delete(&Self::user_stats_key(user_id)).await
+    }
+
+    fn user_stats_key(user_id: &str) -> String {
+        format!("user:{}:stats", user_id)
+    }
+}
@@ -4,8 +4,12 @@ use crate::{
 };
 use diesel_async::{pooled_connection::AsyncDieselConnectionManager, AsyncPgConnection};
 
+mod chat_messages;
+mod game_results;
 mod sessions;
 mod users;
+mod waitlist;
+mod word_packs;
 mod words;
 
 type AsyncDbConnectionPool = bb8::Pool<AsyncDieselConnectionManager<AsyncPgConnection>>;
@@ -31,7 +35,7 @@ impl Database {
         Self { pool }
     }
 
-    pub async fn connection(&self) -> Result<PooledAsyncDbConnection, ApiError> {
+    pub async fn connection(&self) -> Result<PooledAsyncDbConnection<'_>, ApiError> {
         self.pool
             .get()
             .await
@@ -0,0 +1,78 @@
+use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel_async::RunQueryDsl;
+
+use crate::models::chat_message::{ChatMessage, ChatPage, NewChatMessage};
+use crate::models::custom_api_errors::Result;
+use crate::schema;
+
+use super::Database;
+
+impl Database {
+    pub async fn add_chat_message(&self, new_message: NewChatMessage) -> Result<ChatMessage> {
+        use schema::chat_messages;
+
+        let message = diesel::insert_into(chat_messages::table)
+            .values(&new_message)
+            .returning(ChatMessage::as_returning())
+            .get_result(&mut self.connection().await?)
+            .await?;
+
+        self.touch_session(&message.session_id).await?;
+
+        Ok(message)
+    }
+
+    /// The `limit` newest messages older than the message with id `before`
+    pub async fn get_chat_messages(
+        &self,
+        session_id: &str,
+        before: Option<i64>,
+        limit: i64,
+    ) -> Result<ChatPage> {
+        use schema::chat_messages::dsl::{chat_messages, id, session_id as session_id_column};
+
+        let mut query = chat_messages
+            .filter(session_id_column.eq(session_id))
+            .into_boxed();
+
+        if let Some(before) = before {
+            query = query.filter(id.lt(before));
+        }
+
+        // one more than requested tells if there is another page
+        let mut messages = query
+            .order(id.desc())
+            .limit(limit + 1)
+            .select(ChatMessage::as_select())
+            .get_results(&mut self.connection().await?)
+            .await?;
+
+        let has_more = messages.len() as i64 > limit;
+
+        messages.truncate(limit as usize);
+        messages.reverse();
+
+        let next_before = match has_more {
+            true => messages.iter().map(|message| message.id).min(),
+            false => None,
+        };
+
+        Ok(ChatPage {
+            messages,
+            next_before,
+        })
+    }
+
+    pub async fn get_chat_messages_by_user_id(&self, user_id: &str) -> Result<Vec<ChatMessage>> {
+        use schema::chat_messages::dsl::{chat_messages, id, user_id as user_id_column};
+
+        let messages = chat_messages
+            .filter(user_id_column.eq(user_id))
+            .order(id)
+            .select(ChatMessage::as_select())
+            .get_results(&mut self.connection().await?)
+            .await?;
+
+        Ok(messages)
+    }
+}
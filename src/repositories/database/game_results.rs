@@ -0,0 +1,97 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+
+use crate::models::custom_api_errors::{ApiError, Result};
+use crate::models::game_result::{Game, GamePlayer, GameSummary, GameWord, NewGame};
+use crate::schema;
+
+use super::Database;
+
+impl Database {
+    /// Stores the finished game with the results of its players and the guessed words
+    pub async fn save_game(
+        &self,
+        game: NewGame,
+        players: Vec<GamePlayer>,
+        words: Vec<GameWord>,
+    ) -> Result<GameSummary> {
+        use schema::{game_players, game_words, games};
+
+        let mut connection = self.connection().await?;
+
+        let (game, players, words) = connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    diesel::insert_into(games::table)
+                        .values(&game)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    diesel::insert_into(game_players::table)
+                        .values(&players)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    diesel::insert_into(game_words::table)
+                        .values(&words)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    Ok((game, players, words))
+                }
+                .scope_boxed()
+            })
+            .await?;
+
+        Ok(GameSummary::new(game.into(), players, words))
+    }
+
+    /// Summary of the last finished game of the session
+    pub async fn get_game_summary(&self, session_id: &str) -> Result<Option<GameSummary>> {
+        use schema::{game_players, game_words, games};
+
+        let mut connection = self.connection().await?;
+
+        let game = games::table
+            .filter(games::session_id.eq(session_id))
+            .order(games::finished_at.desc())
+            .select(Game::as_select())
+            .first(&mut connection)
+            .await
+            .optional()?;
+
+        let Some(game) = game else {
+            return Ok(None);
+        };
+
+        let players = game_players::table
+            .filter(game_players::game_id.eq(game.id))
+            .select(GamePlayer::as_select())
+            .load(&mut connection)
+            .await?;
+
+        let words = game_words::table
+            .filter(game_words::game_id.eq(game.id))
+            .order(game_words::position)
+            .select(GameWord::as_select())
+            .load(&mut connection)
+            .await?;
+
+        Ok(Some(GameSummary::new(game, players, words)))
+    }
+
+    pub async fn get_game_results_by_user_id(&self, user_id: &str) -> Result<Vec<GamePlayer>> {
+        use schema::game_players::dsl::{game_players, user_id as user_id_column};
+
+        let results = game_players
+            .filter(user_id_column.eq(user_id))
+            .select(GamePlayer::as_select())
+            .load(&mut self.connection().await?)
+            .await?;
+
+        Ok(results)
+    }
+}
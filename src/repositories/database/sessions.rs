@@ -1,13 +1,58 @@
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use std::future::Future;
 
-use crate::models::custom_api_errors::Result;
-use crate::models::session::{NewSession, Session, SessionInfo};
-use crate::models::users_sessions::UsersSession;
+use chrono::NaiveDateTime;
+use diesel::dsl::{count, now};
+use diesel::sql_types::Integer;
+use diesel::{
+    sql_function, BoolExpressionMethods, ExpressionMethods, NullableExpressionMethods, QueryDsl,
+    SelectableHelper, TextExpressionMethods,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+
+use crate::models::custom_api_errors::{ApiError, Result};
+use crate::models::session::{
+    NewSession, Session, SessionCursor, SessionFilter, SessionInfo, SessionJoin, SessionPhase,
+    SessionSort, SessionUpdate,
+};
+use crate::models::users_sessions::{UsersSession, WaitlistEntry};
+use crate::models::word::NewWord;
+use crate::models::word_pack::SessionWordPack;
 use crate::schema;
+use crate::utils::moderation::moderate;
+use crate::utils::session_ids::{generate_session_id, invalid_session_id};
 
+use super::waitlist::{promote_waitlisted, waitlist_position};
 use super::Database;
 
+sql_function!(fn int8(x: Integer) -> BigInt);
+
+/// Generated ids tried before giving up
+const GENERATED_ID_ATTEMPTS: u32 = 10;
+
+/// Calls `create` with generated ids until it doesn't fail because the id is taken
+async fn with_generated_id<F, Fut>(create: F) -> Result<Session>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Session>>,
+{
+    for _ in 0..GENERATED_ID_ATTEMPTS {
+        let id = generate_session_id();
+
+        if invalid_session_id(&id).is_some() {
+            continue;
+        }
+
+        match create(id).await {
+            Err(ApiError::UniqueViolation { .. }) => continue,
+            result => return result,
+        }
+    }
+
+    Err(ApiError::internal(
+        "Could not generate a free session id".to_string(),
+    ))
+}
+
 impl Database {
     pub async fn create_session(&self, new_session: NewSession) -> Result<Session> {
         use schema::{sessions, users_sessions};
@@ -23,6 +68,7 @@ impl Database {
         let new_users_sessions = UsersSession {
             user_id: new_session.admin_user_id.to_string(),
             session_id: session.id.clone(),
+            nickname: None,
         };
 
         diesel::insert_into(users_sessions::table)
@@ -33,15 +79,272 @@ impl Database {
         Ok(session)
     }
 
-    pub async fn get_sessions(&self) -> Result<Vec<Session>> {
-        use schema::sessions::dsl::sessions;
+    /// Creates the session under a generated id, another id is tried if it is already taken
+    pub async fn create_session_with_generated_id(
+        &self,
+        new_session: NewSession,
+    ) -> Result<Session> {
+        with_generated_id(|id| {
+            self.create_session(NewSession {
+                id,
+                ..new_session.clone()
+            })
+        })
+        .await
+    }
 
-        let all_sessions = sessions
-            .select(Session::as_select())
-            .load(&mut self.connection().await?)
+    /// New session with the members, settings and word packs of the session, under a generated id.
+    /// The words are copied too if `keep_words` is set.
+    pub async fn create_rematch(&self, session: &Session, keep_words: bool) -> Result<Session> {
+        with_generated_id(|id| self.copy_session(session, id, keep_words)).await
+    }
+
+    async fn copy_session(
+        &self,
+        session: &Session,
+        new_session_id: String,
+        keep_words: bool,
+    ) -> Result<Session> {
+        use crate::schema::{sessions, sessions_word_packs, users_sessions, words};
+
+        let mut connection = self.connection().await?;
+
+        let new_session = NewSession {
+            id: new_session_id,
+            public: session.public,
+            admin_user_id: session.admin_user_id.clone(),
+            content_filter: session.content_filter.clone(),
+            language: session.language.clone(),
+            max_players: session.max_players,
+        };
+
+        let session_id = session.id.clone();
+
+        connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    let created_session = diesel::insert_into(sessions::table)
+                        .values(&new_session)
+                        .returning(Session::as_returning())
+                        .get_result(conn)
+                        .await?;
+
+                    let members: Vec<UsersSession> = users_sessions::table
+                        .filter(users_sessions::session_id.eq(&session_id))
+                        .select(UsersSession::as_select())
+                        .load(conn)
+                        .await?;
+
+                    let members: Vec<UsersSession> = members
+                        .into_iter()
+                        .map(|member| UsersSession {
+                            session_id: created_session.id.clone(),
+                            ..member
+                        })
+                        .collect();
+
+                    diesel::insert_into(users_sessions::table)
+                        .values(&members)
+                        .execute(conn)
+                        .await?;
+
+                    let pack_ids: Vec<String> = sessions_word_packs::table
+                        .filter(sessions_word_packs::session_id.eq(&session_id))
+                        .select(sessions_word_packs::pack_id)
+                        .load(conn)
+                        .await?;
+
+                    let session_packs: Vec<SessionWordPack> = pack_ids
+                        .into_iter()
+                        .map(|pack_id| SessionWordPack {
+                            session_id: created_session.id.clone(),
+                            pack_id,
+                        })
+                        .collect();
+
+                    diesel::insert_into(sessions_word_packs::table)
+                        .values(&session_packs)
+                        .execute(conn)
+                        .await?;
+
+                    if keep_words {
+                        let session_words: Vec<(String, String, String, String)> = words::table
+                            .filter(words::session_id.eq(&session_id))
+                            .select((
+                                words::word,
+                                words::user_id,
+                                words::normalized,
+                                words::similarity_key,
+                            ))
+                            .load(conn)
+                            .await?;
+
+                        let new_words: Vec<NewWord> = session_words
+                            .into_iter()
+                            .map(|(word, user_id, normalized, similarity_key)| NewWord {
+                                word,
+                                session_id: created_session.id.clone(),
+                                user_id,
+                                normalized,
+                                similarity_key,
+                            })
+                            .collect();
+
+                        diesel::insert_into(words::table)
+                            .values(&new_words)
+                            .execute(conn)
+                            .await?;
+                    }
+
+                    Ok(created_session)
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+
+    pub async fn session_exists(&self, session_id: &str) -> Result<bool> {
+        use crate::schema::sessions::dsl::{id, sessions};
+
+        let count: i64 = sessions
+            .filter(id.eq(session_id))
+            .count()
+            .get_result(&mut self.connection().await?)
+            .await?;
+
+        Ok(count > 0)
+    }
+
+    /// Public sessions matching the filter with their number of members, `filter.limit` at most
+    pub async fn get_public_sessions(&self, filter: SessionFilter) -> Result<Vec<(Session, i64)>> {
+        use schema::{sessions, users_sessions};
+
+        let players = count(users_sessions::user_id.nullable());
+
+        let mut query = sessions::table
+            .left_join(users_sessions::table)
+            .group_by(sessions::id)
+            .select((Session::as_select(), players))
+            .filter(sessions::public.eq(true))
+            .into_boxed();
+
+        if let Some(phase) = filter.phase {
+            query = query.filter(sessions::phase.eq(phase.to_string()));
+        }
+
+        if let Some(language) = filter.language {
+            query = query.filter(sessions::language.eq(language));
+        }
+
+        if let Some(created_since) = filter.created_since {
+            query = query.filter(sessions::created_at.ge(created_since));
+        }
+
+        if let Some(active_since) = filter.active_since {
+            query = query.filter(sessions::last_activity_at.ge(active_since));
+        }
+
+        if let Some(prefix) = filter.id_prefix {
+            let escaped = prefix
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+
+            query = query.filter(sessions::id.like(format!("{}%", escaped)));
+        }
+
+        // the player count can only be filtered once, with the cursor condition if there is one
+        let players_cursor = filter.sort == SessionSort::Players && filter.cursor.is_some();
+
+        // rows after the cursor in the descending (key, id) order
+        if let Some(cursor) = filter.cursor {
+            query = match filter.sort {
+                SessionSort::Created => {
+                    let timestamp = cursor_timestamp(&cursor)?;
+
+                    query.filter(
+                        sessions::created_at.lt(timestamp).or(sessions::created_at
+                            .eq(timestamp)
+                            .and(sessions::id.lt(cursor.id))),
+                    )
+                }
+                SessionSort::Activity => {
+                    let timestamp = cursor_timestamp(&cursor)?;
+
+                    query.filter(
+                        sessions::last_activity_at
+                            .lt(timestamp)
+                            .or(sessions::last_activity_at
+                                .eq(timestamp)
+                                .and(sessions::id.lt(cursor.id))),
+                    )
+                }
+                SessionSort::Players => {
+                    let after_cursor = players
+                        .lt(cursor.key)
+                        .or(players.eq(cursor.key).and(sessions::id.lt(cursor.id)));
+
+                    if filter.free_slots {
+                        query.having(players.lt(int8(sessions::max_players)).and(after_cursor))
+                    } else {
+                        query.having(after_cursor)
+                    }
+                }
+            };
+        }
+
+        if filter.free_slots && !players_cursor {
+            query = query.having(players.lt(int8(sessions::max_players)));
+        }
+
+        query = match filter.sort {
+            SessionSort::Created => query
+                .order(sessions::created_at.desc())
+                .then_order_by(sessions::id.desc()),
+            SessionSort::Players => query
+                .order(players.desc())
+                .then_order_by(sessions::id.desc()),
+            SessionSort::Activity => query
+                .order(sessions::last_activity_at.desc())
+                .then_order_by(sessions::id.desc()),
+        };
+
+        let sessions = query
+            .limit(filter.limit)
+            .load::<(Session, i64)>(&mut self.connection().await?)
+            .await?;
+
+        Ok(sessions)
+    }
+
+    /// Marks the session as active now, used to sort the listing by recent activity
+    pub async fn touch_session(&self, session_id: &str) -> Result<()> {
+        use crate::schema::sessions::dsl::{last_activity_at, sessions};
+
+        diesel::update(sessions.find(session_id))
+            .set(last_activity_at.eq(now))
+            .execute(&mut self.connection().await?)
             .await?;
 
-        Ok(all_sessions)
+        Ok(())
+    }
+
+    pub async fn set_session_phase(
+        &self,
+        session_id: &str,
+        session_phase: SessionPhase,
+    ) -> Result<()> {
+        use crate::schema::sessions::dsl::{last_activity_at, phase, sessions};
+
+        diesel::update(sessions.find(session_id))
+            .set((
+                phase.eq(session_phase.to_string()),
+                last_activity_at.eq(now),
+            ))
+            .execute(&mut self.connection().await?)
+            .await?;
+
+        Ok(())
     }
 
     pub async fn get_session_by_id(&self, session_id: &str) -> Result<Session> {
@@ -56,22 +359,233 @@ impl Database {
         Ok(session)
     }
 
-    pub async fn join_session(&self, session_id: &str, user_id: &str) -> Result<Session> {
-        use crate::schema::users_sessions;
+    pub async fn update_session(&self, session_id: &str, update: SessionUpdate) -> Result<Session> {
+        use crate::schema::sessions::dsl::sessions;
 
-        let new_users_sessions = UsersSession {
-            user_id: user_id.to_string(),
-            session_id: session_id.to_string(),
+        let session = diesel::update(sessions.find(session_id))
+            .set(&update)
+            .returning(Session::as_returning())
+            .get_result(&mut self.connection().await?)
+            .await?;
+
+        Ok(session)
+    }
+
+    /// Adds the user to the session, or to the end of its waitlist if the session is full
+    pub async fn join_session(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        nickname: Option<String>,
+    ) -> Result<SessionJoin> {
+        use crate::schema::{session_waitlist, sessions, users_sessions};
+
+        let mut connection = self.connection().await?;
+
+        let session_id = session_id.to_string();
+        let user_id = user_id.to_string();
+
+        let join = connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    // locked, so concurrent joins can't exceed the limit
+                    let session = sessions::table
+                        .find(&session_id)
+                        .select(Session::as_select())
+                        .for_update()
+                        .first(conn)
+                        .await?;
+
+                    if let Some(nickname) = &nickname {
+                        moderate(nickname, session.content_filter(), "nickname")?;
+                    }
+
+                    let member: i64 = users_sessions::table
+                        .filter(users_sessions::session_id.eq(&session_id))
+                        .filter(users_sessions::user_id.eq(&user_id))
+                        .count()
+                        .get_result(conn)
+                        .await?;
+
+                    if member > 0 {
+                        return Err(ApiError::UniqueViolation {
+                            message: "You are already a member of this session".to_string(),
+                        });
+                    }
+
+                    let members: i64 = users_sessions::table
+                        .filter(users_sessions::session_id.eq(&session_id))
+                        .count()
+                        .get_result(conn)
+                        .await?;
+
+                    if members < i64::from(session.max_players) {
+                        diesel::insert_into(users_sessions::table)
+                            .values(&UsersSession {
+                                user_id,
+                                session_id,
+                                nickname,
+                            })
+                            .execute(conn)
+                            .await?;
+
+                        return Ok(SessionJoin::Joined(session));
+                    }
+
+                    // joining again keeps the place in the queue
+                    diesel::insert_into(session_waitlist::table)
+                        .values(&WaitlistEntry {
+                            session_id: session_id.clone(),
+                            user_id: user_id.clone(),
+                            nickname,
+                        })
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    let position = waitlist_position(conn, &session_id, &user_id)
+                        .await?
+                        .unwrap_or_default();
+
+                    Ok(SessionJoin::Waitlisted { session, position })
+                }
+                .scope_boxed()
+            })
+            .await?;
+
+        if let SessionJoin::Joined(session) = &join {
+            self.touch_session(&session.id).await?;
+        }
+
+        Ok(join)
+    }
+
+    /// Removes a member or waitlisted user, waitlisted users are promoted into the free slot.
+    /// Returns the ids of the promoted users.
+    pub async fn remove_from_session(
+        &self,
+        session_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<String>> {
+        use crate::schema::{session_waitlist, sessions, users_sessions};
+
+        let mut connection = self.connection().await?;
+
+        let session_id = session_id.to_string();
+        let user_id = user_id.to_string();
+
+        connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    let session = sessions::table
+                        .find(&session_id)
+                        .select(Session::as_select())
+                        .for_update()
+                        .first(conn)
+                        .await?;
+
+                    let removed_member = diesel::delete(
+                        users_sessions::table
+                            .filter(users_sessions::session_id.eq(&session_id))
+                            .filter(users_sessions::user_id.eq(&user_id)),
+                    )
+                    .execute(conn)
+                    .await?;
+
+                    let removed_waiting = diesel::delete(
+                        session_waitlist::table
+                            .filter(session_waitlist::session_id.eq(&session_id))
+                            .filter(session_waitlist::user_id.eq(&user_id)),
+                    )
+                    .execute(conn)
+                    .await?;
+
+                    if removed_member + removed_waiting == 0 {
+                        return Err(ApiError::NotFound {
+                            message: "User is not a member of this session".to_string(),
+                        });
+                    }
+
+                    promote_waitlisted(conn, &session).await
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+
+    /// Fills free slots from the waitlist, e.g. after the player limit was raised.
+    /// Returns the ids of the promoted users.
+    pub async fn promote_waitlisted_users(&self, session_id: &str) -> Result<Vec<String>> {
+        use crate::schema::sessions;
+
+        let mut connection = self.connection().await?;
+
+        let session_id = session_id.to_string();
+
+        connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    let session = sessions::table
+                        .find(&session_id)
+                        .select(Session::as_select())
+                        .for_update()
+                        .first(conn)
+                        .await?;
+
+                    promote_waitlisted(conn, &session).await
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+
+    pub async fn is_session_member(&self, session_id: &str, user_id: &str) -> Result<bool> {
+        use crate::schema::users_sessions::dsl::{
+            session_id as session_id_column, user_id as user_id_column, users_sessions,
         };
 
-        diesel::insert_into(users_sessions::table)
-            .values(&new_users_sessions)
+        let members: i64 = users_sessions
+            .filter(session_id_column.eq(session_id))
+            .filter(user_id_column.eq(user_id))
+            .count()
+            .get_result(&mut self.connection().await?)
+            .await?;
+
+        Ok(members > 0)
+    }
+
+    /// Sets or clears (`None`) the nickname of a member
+    pub async fn set_nickname(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        nickname: Option<String>,
+    ) -> Result<()> {
+        use crate::schema::users_sessions::dsl::{
+            nickname as nickname_column, session_id as session_id_column,
+            user_id as user_id_column, users_sessions,
+        };
+
+        if let Some(nickname) = &nickname {
+            let session = self.get_session_by_id(session_id).await?;
+
+            moderate(nickname, session.content_filter(), "nickname")?;
+        }
+
+        let updated = diesel::update(users_sessions)
+            .filter(session_id_column.eq(session_id))
+            .filter(user_id_column.eq(user_id))
+            .set(nickname_column.eq(nickname))
             .execute(&mut self.connection().await?)
             .await?;
 
-        let session = self.get_session_by_id(session_id).await?;
+        if updated == 0 {
+            return Err(ApiError::NotFound {
+                message: "You are not a member of this session".to_string(),
+            });
+        }
 
-        Ok(session)
+        Ok(())
     }
 
     pub async fn get_session_info(&self, session_id: &str) -> Result<SessionInfo> {
@@ -102,3 +616,9 @@ impl Database {
         Ok(sessions)
     }
 }
+
+fn cursor_timestamp(cursor: &SessionCursor) -> Result<NaiveDateTime> {
+    NaiveDateTime::from_timestamp_micros(cursor.key).ok_or(ApiError::BadRequest {
+        message: "Invalid cursor".to_string(),
+    })
+}
@@ -1,11 +1,19 @@
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
-use diesel_async::RunQueryDsl;
+use chrono::NaiveDateTime;
+use diesel::{
+    dsl::{exists, not},
+    BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, SelectableHelper,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
 
-use crate::models::custom_api_errors::Result;
-use crate::models::user::{FullUserInfo, NewUser, User};
+use crate::models::custom_api_errors::{ApiError, Result};
+use crate::models::session::Session;
+use crate::models::user::{
+    Account, Credentials, FullUserInfo, NewCredentials, NewUser, ProfileUpdate, User,
+    UserDataExport,
+};
 use crate::schema;
 
-use super::Database;
+use super::{waitlist::promote_waitlisted, Database};
 
 impl Database {
     pub async fn create_user(&self, user: NewUser) -> Result<User> {
@@ -31,6 +39,24 @@ impl Database {
         Ok(user)
     }
 
+    pub async fn update_profile(&self, user_id: &str, profile: ProfileUpdate) -> Result<Account> {
+        use schema::users::dsl::{id, users};
+
+        // an empty changeset is an error in diesel
+        if profile.is_empty() {
+            return self.get_account_by_id(user_id).await;
+        }
+
+        let account = diesel::update(users)
+            .filter(id.eq(user_id))
+            .set(&profile)
+            .returning(Account::as_returning())
+            .get_result(&mut self.connection().await?)
+            .await?;
+
+        Ok(account)
+    }
+
     pub async fn get_user_by_id(&self, user_id: &str) -> Result<User> {
         use schema::users::dsl::{id, users};
 
@@ -43,18 +69,83 @@ impl Database {
         Ok(user)
     }
 
+    pub async fn get_account_by_id(&self, user_id: &str) -> Result<Account> {
+        use schema::users::dsl::{id, users};
+
+        let account = users
+            .filter(id.eq(user_id))
+            .select(Account::as_select())
+            .first(&mut self.connection().await?)
+            .await?;
+
+        Ok(account)
+    }
+
+    pub async fn get_credentials_by_id(&self, user_id: &str) -> Result<Credentials> {
+        use schema::users::dsl::{id, users};
+
+        let credentials = users
+            .filter(id.eq(user_id))
+            .select(Credentials::as_select())
+            .first(&mut self.connection().await?)
+            .await?;
+
+        Ok(credentials)
+    }
+
+    /// Looks up a registered user by email or username
+    pub async fn get_credentials_by_login(&self, login: &str) -> Result<Credentials> {
+        use schema::users::dsl::{email, password_hash, username, users};
+
+        let credentials = users
+            .filter(email.eq(login).or(username.eq(login)))
+            .filter(password_hash.is_not_null())
+            .select(Credentials::as_select())
+            .first(&mut self.connection().await?)
+            .await?;
+
+        Ok(credentials)
+    }
+
+    /// Adds credentials to an existing (anonymous) user, keeping all of their data
+    pub async fn register_user(
+        &self,
+        user_id: &str,
+        credentials: NewCredentials,
+    ) -> Result<Account> {
+        use schema::users::dsl::{id, users};
+
+        let account = diesel::update(users)
+            .filter(id.eq(user_id))
+            .set(&credentials)
+            .returning(Account::as_returning())
+            .get_result(&mut self.connection().await?)
+            .await?;
+
+        Ok(account)
+    }
+
+    /// Members of a session, with their session nickname as name if they set one
     pub async fn get_users_by_session_id(&self, session_id: &str) -> Result<Vec<User>> {
         use schema::users::table as users_table;
-        use schema::users_sessions::{dsl::users_sessions, session_id as session_id_column};
+        use schema::users_sessions::{
+            dsl::users_sessions, nickname, session_id as session_id_column,
+        };
 
         let users = users_sessions
             .inner_join(users_table)
             .filter(session_id_column.eq(session_id))
-            .select(User::as_select())
-            .get_results::<User>(&mut self.connection().await?)
+            .select((User::as_select(), nickname))
+            .get_results::<(User, Option<String>)>(&mut self.connection().await?)
             .await?;
 
-        Ok(users)
+        Ok(users
+            .into_iter()
+            .map(|(user, session_nickname)| User {
+                name: session_nickname.or(user.name),
+                ..user
+            })
+            .collect())
     }
 
     pub async fn get_full_user_info(&self, user_id: &str) -> Result<FullUserInfo> {
@@ -64,4 +155,136 @@ impl Database {
 
         Ok(FullUserInfo { user, sessions })
     }
+
+    pub async fn get_user_data_export(&self, user_id: &str) -> Result<UserDataExport> {
+        let account = self.get_account_by_id(user_id).await?;
+
+        let sessions = self.get_sessions_by_user(user_id).await?;
+
+        let words = self.get_words_by_user_id(user_id).await?;
+
+        let chat_messages = self.get_chat_messages_by_user_id(user_id).await?;
+
+        let games = self.get_game_results_by_user_id(user_id).await?;
+
+        Ok(UserDataExport {
+            exported_at: chrono::Utc::now().naive_utc(),
+            account,
+            sessions,
+            words,
+            chat_messages,
+            games,
+        })
+    }
+
+    /// Deletes nameless anonymous users created before `created_before` that never joined a session,
+    /// registered accounts are kept
+    pub async fn delete_abandoned_users(&self, created_before: NaiveDateTime) -> Result<usize> {
+        use schema::{sessions, users, users_sessions};
+
+        let deleted = diesel::delete(
+            users::table
+                .filter(users::name.is_null())
+                .filter(users::password_hash.is_null())
+                .filter(users::created_at.lt(created_before))
+                .filter(not(exists(
+                    users_sessions::table.filter(users_sessions::user_id.eq(users::id)),
+                )))
+                .filter(not(exists(
+                    sessions::table.filter(sessions::admin_user_id.eq(users::id)),
+                ))),
+        )
+        .execute(&mut self.connection().await?)
+        .await?;
+
+        Ok(deleted)
+    }
+
+    /// Deletes the user, their words, chat messages and memberships are removed by the cascade rules.
+    /// Sessions they administrate are handed over to another member or deleted if they are the only one.
+    /// The freed slots are filled from the waitlists, returns the promoted users per remaining session.
+    pub async fn delete_user(&self, user_id: &str) -> Result<Vec<(String, Vec<String>)>> {
+        use schema::{session_waitlist, sessions, users, users_sessions};
+
+        let mut connection = self.connection().await?;
+
+        let user_id = user_id.to_string();
+
+        connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    // locked before the memberships are gone, so nobody takes the free slots first
+                    let joined_sessions: Vec<Session> = sessions::table
+                        .filter(
+                            sessions::admin_user_id
+                                .eq(&user_id)
+                                .or(sessions::id.eq_any(
+                                    users_sessions::table
+                                        .filter(users_sessions::user_id.eq(&user_id))
+                                        .select(users_sessions::session_id),
+                                ))
+                                .or(sessions::id.eq_any(
+                                    session_waitlist::table
+                                        .filter(session_waitlist::user_id.eq(&user_id))
+                                        .select(session_waitlist::session_id),
+                                )),
+                        )
+                        .order(sessions::id)
+                        .select(Session::as_select())
+                        .for_update()
+                        .load(conn)
+                        .await?;
+
+                    let mut deleted_session_ids = vec![];
+
+                    for session in joined_sessions
+                        .iter()
+                        .filter(|s| s.admin_user_id == user_id)
+                    {
+                        let new_admin: Option<String> = users_sessions::table
+                            .filter(users_sessions::session_id.eq(&session.id))
+                            .filter(users_sessions::user_id.ne(&user_id))
+                            .select(users_sessions::user_id)
+                            .order(users_sessions::user_id)
+                            .first(conn)
+                            .await
+                            .optional()?;
+
+                        match new_admin {
+                            Some(new_admin) => {
+                                diesel::update(sessions::table.find(&session.id))
+                                    .set(sessions::admin_user_id.eq(new_admin))
+                                    .execute(conn)
+                                    .await?;
+                            }
+                            None => {
+                                diesel::delete(sessions::table.find(&session.id))
+                                    .execute(conn)
+                                    .await?;
+
+                                deleted_session_ids.push(session.id.clone());
+                            }
+                        }
+                    }
+
+                    diesel::delete(users::table.find(&user_id))
+                        .execute(conn)
+                        .await?;
+
+                    let mut promoted = vec![];
+
+                    for session in joined_sessions
+                        .iter()
+                        .filter(|s| !deleted_session_ids.contains(&s.id))
+                    {
+                        promoted
+                            .push((session.id.clone(), promote_waitlisted(conn, session).await?));
+                    }
+
+                    Ok(promoted)
+                }
+                .scope_boxed()
+            })
+            .await
+    }
 }
@@ -0,0 +1,121 @@
+use diesel::{ExpressionMethods, QueryDsl, QueryResult};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+
+use crate::models::custom_api_errors::Result;
+use crate::models::session::Session;
+use crate::models::users_sessions::UsersSession;
+use crate::schema;
+
+use super::Database;
+
+impl Database {
+    /// Ids of the waitlisted users, next in line first
+    pub async fn get_waitlist(&self, session_id: &str) -> Result<Vec<String>> {
+        let mut connection = self.connection().await?;
+
+        let waitlist = waitlist(&mut connection, session_id).await?;
+
+        Ok(waitlist)
+    }
+
+    /// Position of the user on the waitlist starting at 1, `None` if they are not waiting
+    pub async fn get_waitlist_position(
+        &self,
+        session_id: &str,
+        user_id: &str,
+    ) -> Result<Option<i64>> {
+        let mut connection = self.connection().await?;
+
+        let position = waitlist_position(&mut connection, session_id, user_id).await?;
+
+        Ok(position)
+    }
+}
+
+async fn waitlist(conn: &mut AsyncPgConnection, session_id: &str) -> QueryResult<Vec<String>> {
+    use schema::session_waitlist::dsl::{
+        created_at, session_id as session_id_column, session_waitlist, user_id,
+    };
+
+    session_waitlist
+        .filter(session_id_column.eq(session_id))
+        .order((created_at.asc(), user_id.asc()))
+        .select(user_id)
+        .load(conn)
+        .await
+}
+
+pub(super) async fn waitlist_position(
+    conn: &mut AsyncPgConnection,
+    session_id: &str,
+    user_id: &str,
+) -> QueryResult<Option<i64>> {
+    let waitlist = waitlist(conn, session_id).await?;
+
+    Ok(waitlist
+        .iter()
+        .position(|id| id == user_id)
+        .map(|index| index as i64 + 1))
+}
+
+/// Moves the longest waiting users into the free slots of the session and returns their ids.
+/// The session row has to be locked by the calling transaction.
+pub(super) async fn promote_waitlisted(
+    conn: &mut AsyncPgConnection,
+    session: &Session,
+) -> Result<Vec<String>> {
+    use schema::{session_waitlist, users_sessions};
+
+    let members: i64 = users_sessions::table
+        .filter(users_sessions::session_id.eq(&session.id))
+        .count()
+        .get_result(conn)
+        .await?;
+
+    let free_slots = i64::from(session.max_players) - members;
+
+    if free_slots <= 0 {
+        return Ok(vec![]);
+    }
+
+    let promoted: Vec<(String, Option<String>)> = session_waitlist::table
+        .filter(session_waitlist::session_id.eq(&session.id))
+        .order((
+            session_waitlist::created_at.asc(),
+            session_waitlist::user_id.asc(),
+        ))
+        .select((session_waitlist::user_id, session_waitlist::nickname))
+        .limit(free_slots)
+        .load(conn)
+        .await?;
+
+    if promoted.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let promoted_ids: Vec<String> = promoted.iter().map(|(id, _)| id.clone()).collect();
+
+    diesel::delete(
+        session_waitlist::table
+            .filter(session_waitlist::session_id.eq(&session.id))
+            .filter(session_waitlist::user_id.eq_any(&promoted_ids)),
+    )
+    .execute(conn)
+    .await?;
+
+    let new_members: Vec<UsersSession> = promoted
+        .into_iter()
+        .map(|(user_id, nickname)| UsersSession {
+            user_id,
+            session_id: session.id.clone(),
+            nickname,
+        })
+        .collect();
+
+    diesel::insert_into(users_sessions::table)
+        .values(&new_members)
+        .execute(conn)
+        .await?;
+
+    Ok(promoted_ids)
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use diesel::{
+    dsl::count_star, sql_function, ExpressionMethods, JoinOnDsl, QueryDsl, SelectableHelper,
+};
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+
+use crate::models::custom_api_errors::{ApiError, Result};
+use crate::models::word_pack::{
+    NewWordPack, SessionWordPack, WordPack, WordPackInfo, WordPackWord,
+};
+use crate::schema;
+
+use super::Database;
+
+sql_function!(fn random() -> Double);
+
+impl Database {
+    /// Creates or updates the pack, words already in the pack are kept
+    pub async fn import_word_pack(&self, pack: NewWordPack, words: Vec<String>) -> Result<usize> {
+        use schema::{word_pack_words, word_packs};
+
+        let mut connection = self.connection().await?;
+
+        connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    diesel::insert_into(word_packs::table)
+                        .values(&pack)
+                        .on_conflict(word_packs::id)
+                        .do_update()
+                        .set(&pack)
+                        .execute(conn)
+                        .await?;
+
+                    let pack_words: Vec<WordPackWord> = words
+                        .into_iter()
+                        .map(|word| WordPackWord {
+                            pack_id: pack.id.clone(),
+                            word,
+                        })
+                        .collect();
+
+                    let inserted = diesel::insert_into(word_pack_words::table)
+                        .values(&pack_words)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    Ok(inserted)
+                }
+                .scope_boxed()
+            })
+            .await
+    }
+
+    pub async fn get_word_packs(&self, language: Option<&str>) -> Result<Vec<WordPackInfo>> {
+        use schema::{word_pack_words, word_packs};
+
+        let mut connection = self.connection().await?;
+
+        let mut query = word_packs::table.into_boxed();
+
+        if let Some(language) = language {
+            query = query.filter(word_packs::language.eq(language));
+        }
+
+        let packs = query
+            .order((word_packs::category, word_packs::name))
+            .select(WordPack::as_select())
+            .load(&mut connection)
+            .await?;
+
+        let counts: HashMap<String, i64> = word_pack_words::table
+            .group_by(word_pack_words::pack_id)
+            .select((word_pack_words::pack_id, count_star()))
+            .load::<(String, i64)>(&mut connection)
+            .await?
+            .into_iter()
+            .collect();
+
+        Ok(packs
+            .into_iter()
+            .map(|pack| WordPackInfo {
+                number_of_words: counts.get(&pack.id).copied().unwrap_or_default(),
+                pack,
+            })
+            .collect())
+    }
+
+    pub async fn get_session_word_packs(&self, session_id: &str) -> Result<Vec<WordPack>> {
+        use schema::{sessions_word_packs, word_packs};
+
+        let packs = sessions_word_packs::table
+            .inner_join(word_packs::table)
+            .filter(sessions_word_packs::session_id.eq(session_id))
+            .order(word_packs::name)
+            .select(WordPack::as_select())
+            .load(&mut self.connection().await?)
+            .await?;
+
+        Ok(packs)
+    }
+
+    /// Replaces the packs selected for the session
+    pub async fn set_session_word_packs(
+        &self,
+        session_id: &str,
+        pack_ids: Vec<String>,
+    ) -> Result<()> {
+        use schema::sessions_word_packs;
+
+        let mut connection = self.connection().await?;
+
+        let session_packs: Vec<SessionWordPack> = pack_ids
+            .into_iter()
+            .map(|pack_id| SessionWordPack {
+                session_id: session_id.to_string(),
+                pack_id,
+            })
+            .collect();
+
+        let session_id = session_id.to_string();
+
+        connection
+            .transaction::<_, ApiError, _>(|conn| {
+                async move {
+                    diesel::delete(
+                        sessions_word_packs::table
+                            .filter(sessions_word_packs::session_id.eq(&session_id)),
+                    )
+                    .execute(conn)
+                    .await?;
+
+                    diesel::insert_into(sessions_word_packs::table)
+                        .values(&session_packs)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .await?;
+
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await
+            .map_err(|e| match e {
+                ApiError::ForeignKeyViolation { .. } => ApiError::NotFound {
+                    message: "Word pack not found".to_string(),
+                },
+                _ => e,
+            })
+    }
+
+    /// Random words of the packs selected for the session
+    pub async fn get_random_pack_words(&self, session_id: &str, limit: i64) -> Result<Vec<String>> {
+        use schema::{sessions_word_packs, word_pack_words};
+
+        let words = word_pack_words::table
+            .inner_join(
+                sessions_word_packs::table
+                    .on(sessions_word_packs::pack_id.eq(word_pack_words::pack_id)),
+            )
+            .filter(sessions_word_packs::session_id.eq(session_id))
+            .order(random())
+            .limit(limit)
+            .select(word_pack_words::word)
+            .load(&mut self.connection().await?)
+            .await?;
+
+        Ok(words)
+    }
+}
@@ -1,21 +1,77 @@
-use diesel::{ExpressionMethods, QueryDsl, SelectableHelper};
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 
-use crate::models::custom_api_errors::Result;
+use crate::models::custom_api_errors::{ApiError, Result};
 use crate::models::word::{NewWord, Word};
 use crate::schema;
+use crate::utils::{guesses::near_duplicate_keys, moderation::moderate, validators::valid_word};
 
 use super::Database;
 
 impl Database {
+    /// Adds the word unless the content filter of the session rejects it
+    /// or the session already has the same or a very similar word
     pub async fn add_word_to_session(&self, new_word: NewWord) -> Result<Word> {
         use schema::words;
 
+        // websocket clients don't go through the dto validation
+        if let Some((message, code)) = valid_word(&new_word.word, "Word") {
+            return Err(ApiError::Validation {
+                message,
+                code,
+                field: Some("word".to_string()),
+            });
+        }
+
+        let session = self.get_session_by_id(&new_word.session_id).await?;
+
+        moderate(&new_word.word, session.content_filter(), "word")?;
+
+        let mut connection = self.connection().await?;
+
+        let similar_words: Vec<(String, String)> = words::table
+            .filter(words::session_id.eq(&new_word.session_id))
+            .filter(
+                words::normalized
+                    .eq(&new_word.normalized)
+                    .or(words::similarity_key.eq_any(near_duplicate_keys(&new_word.word))),
+            )
+            .select((words::word, words::normalized))
+            .load(&mut connection)
+            .await?;
+
+        let duplicate = |existing: &str| ApiError::UniqueViolation {
+            message: format!("Word '{existing}' already in session"),
+        };
+
+        if let Some((existing, _)) = similar_words
+            .iter()
+            .find(|(_, normalized)| *normalized == new_word.normalized)
+        {
+            return Err(duplicate(existing));
+        }
+
+        // plurals and accents of a word already in the session
+        if let [(existing, _), ..] = similar_words.as_slice() {
+            return Err(ApiError::Validation {
+                message: format!("Word '{}' is too similar to '{existing}'", new_word.word),
+                code: "near_duplicate".to_string(),
+                field: Some("word".to_string()),
+            });
+        }
+
+        // the unique index still catches concurrent inserts of the same word
         let word = diesel::insert_into(words::table)
             .values(&new_word)
             .returning(Word::as_returning())
-            .get_result(&mut self.connection().await?)
-            .await?;
+            .get_result(&mut connection)
+            .await
+            .map_err(|e| match ApiError::from(e) {
+                ApiError::UniqueViolation { .. } => duplicate(&new_word.word),
+                e => e,
+            })?;
+
+        self.touch_session(&new_word.session_id).await?;
 
         Ok(word)
     }
@@ -43,4 +99,17 @@ impl Database {
 
         Ok(w)
     }
+
+    pub async fn get_words_by_user_id(&self, user_id: &str) -> Result<Vec<Word>> {
+        use crate::schema::words::dsl::{created_at, user_id as user_id_column, words};
+
+        let w = words
+            .filter(user_id_column.eq(user_id))
+            .order(created_at)
+            .select(Word::as_select())
+            .get_results(&mut self.connection().await?)
+            .await?;
+
+        Ok(w)
+    }
 }
@@ -1,8 +1,11 @@
+use actix::Addr;
 use actix_session::Session;
 use actix_web::{
-    get, post,
+    delete, get,
+    http::header::ContentDisposition,
+    patch, post,
     web::{self, Data},
-    HttpResponse, Responder,
+    HttpRequest, HttpResponse, Responder,
 };
 use actix_web_validator::Json;
 
@@ -10,9 +13,20 @@ use crate::{
     extractors::user_id::UserId,
     models::{
         custom_api_errors::ApiError,
-        dtos::user::NewUserDto,
-        user::{NewUser, User},
+        dtos::{
+            token::{LogoutDto, TokenRequestDto},
+            user::{LoginDto, NewUserDto, RegisterDto, UpdateProfileDto},
+        },
+        token::TokenPair,
+        user::{NewCredentials, NewUser, ProfileUpdate},
     },
+    utils::{
+        passwords::{hash_password, verify_password},
+        stats,
+        tokens::{create_access_token, ACCESS_TOKEN_TTL_SECS},
+        validators::normalize_display_name,
+    },
+    websocket::{server::CharadeServer, MembersChanged, UserUpdated},
     AppContext, Response,
 };
 
@@ -22,57 +36,339 @@ pub const SESSION_USER_ID: &str = "user_id";
 async fn authenticate(
     session: Session,
     ctx: web::Data<AppContext>,
+    srv: web::Data<Addr<CharadeServer>>,
     user_body: Json<NewUserDto>,
 ) -> Result<impl Responder, ApiError> {
     let user_body = user_body.into_inner();
 
     let user_id = session.get::<String>(SESSION_USER_ID)?;
+    let name = user_body.name.map(|name| normalize_display_name(&name));
 
-    let user: User;
-
-    if let Some(user_id) = user_id {
-        user = ctx
+    // the user behind the cookie might have been removed by the cleanup job in the meantime
+    let existing_user = match user_id {
+        Some(user_id) => match ctx
             .db
             .update_user(NewUser {
                 id: user_id,
-                name: user_body.name.map(|name| name.trim().to_string()),
-            })
-            .await?;
-    } else {
-        user = ctx
-            .db
-            .create_user(NewUser {
-                id: uuid::Uuid::new_v4().to_string(),
-                name: user_body.name.map(|name| name.trim().to_string()),
+                name: name.clone(),
             })
-            .await?;
+            .await
+        {
+            Ok(user) => Some(user),
+            Err(ApiError::NotFound { .. }) => None,
+            Err(e) => return Err(e),
+        },
+        None => None,
+    };
 
-        session.insert(SESSION_USER_ID, user.id.clone())?;
-    }
+    let user = match existing_user {
+        Some(user) => {
+            srv.do_send(UserUpdated {
+                id: user.id.clone(),
+            });
+
+            user
+        }
+        None => {
+            let user = ctx
+                .db
+                .create_user(NewUser {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name,
+                })
+                .await?;
+
+            session.insert(SESSION_USER_ID, user.id.clone())?;
+
+            user
+        }
+    };
 
     Ok(HttpResponse::Ok().json(user))
 }
 
+/// Returns the user of the session, creating an anonymous one if there is none yet
+async fn session_user_id(session: &Session, ctx: &AppContext) -> Result<String, ApiError> {
+    if let Some(user_id) = session.get::<String>(SESSION_USER_ID)? {
+        match ctx.db.get_user_by_id(&user_id).await {
+            Ok(user) => return Ok(user.id),
+            Err(ApiError::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let user = ctx
+        .db
+        .create_user(NewUser {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: None,
+        })
+        .await?;
+
+    session.insert(SESSION_USER_ID, user.id.clone())?;
+
+    Ok(user.id)
+}
+
+#[post("/auth/register")]
+async fn register(
+    session: Session,
+    ctx: web::Data<AppContext>,
+    register_body: Json<RegisterDto>,
+) -> Result<impl Responder, ApiError> {
+    let register_body = register_body.into_inner();
+
+    let user_id = session_user_id(&session, &ctx).await?;
+
+    let credentials = ctx.db.get_credentials_by_id(&user_id).await?;
+
+    if credentials.password_hash.is_some() {
+        return Err(ApiError::BadRequest {
+            message: "This account is already registered".to_string(),
+        });
+    }
+
+    let password = register_body.password;
+    let password_hash = web::block(move || hash_password(&password)).await??;
+
+    let account = ctx
+        .db
+        .register_user(
+            &user_id,
+            NewCredentials {
+                email: register_body.email.map(|email| email.trim().to_lowercase()),
+                username: register_body
+                    .username
+                    .map(|name| normalize_display_name(&name)),
+                password_hash,
+            },
+        )
+        .await
+        .map_err(|e| match e {
+            ApiError::UniqueViolation { .. } => ApiError::UniqueViolation {
+                message: "Email or username already taken".to_string(),
+            },
+            _ => e,
+        })?;
+
+    session.renew();
+
+    Ok(HttpResponse::Ok().json(account))
+}
+
+/// Checks email/username and password, returns the id of the registered user
+async fn verify_login(
+    ctx: &AppContext,
+    email_or_username: &str,
+    password: &str,
+) -> Result<String, ApiError> {
+    let invalid_login = || ApiError::Unauthorized {
+        message: "Invalid login or password".to_string(),
+    };
+
+    let credentials = ctx
+        .db
+        .get_credentials_by_login(&normalize_display_name(email_or_username).to_lowercase())
+        .await
+        .map_err(|e| match e {
+            ApiError::NotFound { .. } => invalid_login(),
+            _ => e,
+        })?;
+
+    let password_hash = credentials.password_hash.ok_or_else(invalid_login)?;
+    let password = password.to_string();
+
+    let valid = web::block(move || verify_password(&password, &password_hash)).await??;
+
+    if !valid {
+        return Err(invalid_login());
+    }
+
+    Ok(credentials.id)
+}
+
+#[post("/auth/login")]
+async fn login(
+    session: Session,
+    ctx: web::Data<AppContext>,
+    login_body: Json<LoginDto>,
+) -> Result<impl Responder, ApiError> {
+    let user_id = verify_login(&ctx, &login_body.login, &login_body.password).await?;
+
+    session.renew();
+    session.insert(SESSION_USER_ID, user_id.clone())?;
+
+    let account = ctx.db.get_account_by_id(&user_id).await?;
+
+    Ok(HttpResponse::Ok().json(account))
+}
+
+/// Issues access and refresh tokens for clients that can't use the session cookie
+#[post("/auth/token")]
+async fn create_token(
+    req: HttpRequest,
+    ctx: web::Data<AppContext>,
+    token_body: web::Json<TokenRequestDto>,
+) -> Result<impl Responder, ApiError> {
+    let user_id = match token_body.into_inner() {
+        // only the cookie, an access token must not be turned into a refresh token
+        TokenRequestDto::Session => UserId::from_session(&req)?.0,
+        TokenRequestDto::Password {
+            login: email_or_username,
+            password,
+        } => verify_login(&ctx, &email_or_username, &password).await?,
+        TokenRequestDto::RefreshToken { refresh_token } => {
+            ctx.cache.consume_refresh_token(&refresh_token).await?
+        }
+    };
+
+    // makes sure tokens are never issued for deleted users
+    let user = ctx.db.get_user_by_id(&user_id).await?;
+
+    Ok(HttpResponse::Ok().json(TokenPair {
+        access_token: create_access_token(&user.id)?,
+        token_type: "Bearer".to_string(),
+        expires_in: ACCESS_TOKEN_TTL_SECS,
+        refresh_token: ctx.cache.create_refresh_token(&user.id).await?,
+    }))
+}
+
 #[get("/account")]
 async fn get_account(user_id: UserId, ctx: Data<AppContext>) -> Result<impl Responder, ApiError> {
-    let user = ctx.db.get_user_by_id(&user_id.0).await?;
+    let account = ctx.db.get_account_by_id(&user_id.0).await?;
 
-    Ok(HttpResponse::Ok().json(user))
+    Ok(HttpResponse::Ok().json(account))
 }
 
 #[post("/logout")]
-async fn logout(session: Session) -> Result<impl Responder, ApiError> {
-    session
-        .remove(SESSION_USER_ID)
-        .ok_or(ApiError::BadRequest {
+async fn logout(
+    session: Session,
+    ctx: web::Data<AppContext>,
+    logout_body: Option<web::Json<LogoutDto>>,
+) -> Result<impl Responder, ApiError> {
+    let refresh_token = logout_body.and_then(|body| body.into_inner().refresh_token);
+
+    let had_refresh_token = refresh_token.is_some();
+
+    if let Some(refresh_token) = refresh_token {
+        ctx.cache.revoke_refresh_token(&refresh_token).await?;
+    }
+
+    let has_session = session.get::<String>(SESSION_USER_ID)?.is_some();
+
+    if !has_session && !had_refresh_token {
+        return Err(ApiError::BadRequest {
             message: "Not logged in".to_string(),
-        })?;
+        });
+    }
+
+    // deletes the state from the store, so copies of the cookie stop working as well
+    session.purge();
 
     Ok(HttpResponse::Ok().json(Response {
         message: "Logged out".to_string(),
     }))
 }
 
+/// Ends every session and revokes every refresh token of the user
+#[post("/logout/all")]
+async fn logout_everywhere(
+    session: Session,
+    user_id: UserId,
+    ctx: web::Data<AppContext>,
+) -> Result<impl Responder, ApiError> {
+    ctx.cache.delete_user_auth_sessions(&user_id.0).await?;
+    ctx.cache.revoke_user_refresh_tokens(&user_id.0).await?;
+
+    session.purge();
+
+    Ok(HttpResponse::Ok().json(Response {
+        message: "Logged out everywhere".to_string(),
+    }))
+}
+
+#[patch("/account")]
+async fn update_account(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+    profile_body: Json<UpdateProfileDto>,
+) -> Result<impl Responder, ApiError> {
+    let profile_body = profile_body.into_inner();
+
+    let account = ctx
+        .db
+        .update_profile(
+            &user_id.0,
+            ProfileUpdate {
+                name: profile_body.name.map(|name| normalize_display_name(&name)),
+                avatar_color: profile_body.avatar_color.map(|color| color.to_lowercase()),
+                avatar_emoji: profile_body.avatar_emoji,
+                locale: profile_body.locale,
+            },
+        )
+        .await?;
+
+    srv.do_send(UserUpdated {
+        id: account.id.clone(),
+    });
+
+    Ok(HttpResponse::Ok().json(account))
+}
+
+/// Deletes the account with all of its data and ends all of its sessions
+#[delete("/account")]
+async fn delete_account(
+    session: Session,
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+) -> Result<impl Responder, ApiError> {
+    let promoted_per_session = ctx.db.delete_user(&user_id.0).await?;
+
+    for (session_id, promoted) in promoted_per_session {
+        srv.do_send(MembersChanged {
+            session_id,
+            kicked: None,
+            promoted,
+        });
+    }
+
+    ctx.cache.delete_user_auth_sessions(&user_id.0).await?;
+    ctx.cache.revoke_user_refresh_tokens(&user_id.0).await?;
+
+    session.purge();
+
+    Ok(HttpResponse::Ok().json(Response {
+        message: "Account deleted".to_string(),
+    }))
+}
+
+#[get("/account/export")]
+async fn export_account(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+) -> Result<impl Responder, ApiError> {
+    let export = ctx.db.get_user_data_export(&user_id.0).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(ContentDisposition::attachment(format!(
+            "charade-export-{}.json",
+            user_id.0
+        )))
+        .json(export))
+}
+
+#[get("/account/stats")]
+async fn get_account_stats(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+) -> Result<impl Responder, ApiError> {
+    let stats = stats::user_stats(&ctx.db, &ctx.cache, &user_id.0).await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
 #[get("/account/full")]
 async fn get_full_user_info(
     user_id: UserId,
@@ -86,7 +382,15 @@ async fn get_full_user_info(
 pub fn config(config: &mut web::ServiceConfig) {
     config
         .service(authenticate)
+        .service(register)
+        .service(login)
+        .service(create_token)
         .service(get_account)
         .service(logout)
-        .service(get_full_user_info);
+        .service(logout_everywhere)
+        .service(get_full_user_info)
+        .service(get_account_stats)
+        .service(update_account)
+        .service(delete_account)
+        .service(export_account);
 }
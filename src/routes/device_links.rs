@@ -0,0 +1,73 @@
+use actix_session::Session;
+use actix_web::{
+    delete, get, post,
+    web::{self, Data, Path},
+    HttpResponse, Responder,
+};
+use actix_web_validator::Json;
+
+use crate::{
+    extractors::user_id::UserId,
+    models::{custom_api_errors::ApiError, dtos::device_link::RedeemDeviceLinkDto},
+    routes::SESSION_USER_ID,
+    AppContext, Response,
+};
+
+#[post("")]
+async fn create_device_link(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+) -> Result<impl Responder, ApiError> {
+    let link = ctx.cache.create_device_link(&user_id.0).await?;
+
+    Ok(HttpResponse::Ok().json(link))
+}
+
+#[get("")]
+async fn get_device_links(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+) -> Result<impl Responder, ApiError> {
+    let links = ctx.cache.get_device_links(&user_id.0).await?;
+
+    Ok(HttpResponse::Ok().json(links))
+}
+
+#[post("/redeem")]
+async fn redeem_device_link(
+    session: Session,
+    ctx: Data<AppContext>,
+    redeem_body: Json<RedeemDeviceLinkDto>,
+) -> Result<impl Responder, ApiError> {
+    let link = ctx.cache.redeem_device_link(&redeem_body.code).await?;
+
+    let account = ctx.db.get_account_by_id(&link.user_id).await?;
+
+    session.renew();
+    session.insert(SESSION_USER_ID, account.id.clone())?;
+
+    Ok(HttpResponse::Ok().json(account))
+}
+
+#[delete("/{code}")]
+async fn revoke_device_link(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    code: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    ctx.cache.revoke_device_link(&user_id.0, &code).await?;
+
+    Ok(HttpResponse::Ok().json(Response {
+        message: "Device link revoked".to_string(),
+    }))
+}
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(
+        web::scope("/auth/links")
+            .service(create_device_link)
+            .service(get_device_links)
+            .service(redeem_device_link)
+            .service(revoke_device_link),
+    );
+}
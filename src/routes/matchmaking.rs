@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use actix_web::{
+    post,
+    web::{self, Data},
+    HttpResponse, Responder,
+};
+use chrono::Utc;
+
+use crate::{
+    extractors::{optional_json::OptionalJson, user_id::UserId},
+    models::{
+        custom_api_errors::ApiError,
+        dtos::matchmaking::MatchmakingDto,
+        session::{
+            NewSession, Session, SessionFilter, SessionJoin, SessionMatch, SessionPhase,
+            SessionSort, DEFAULT_LANGUAGE,
+        },
+    },
+    repositories::cache::Cache,
+    utils::{envs, moderation::Strictness, validators::normalize_display_name},
+    AppContext,
+};
+
+/// Matchmaking of the same language waits this often for the one running before giving up
+const LOCK_ATTEMPTS: u32 = 20;
+
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Fullest lobbies considered, the one quick play is filling is preferred among them
+const LOBBY_CANDIDATES: i64 = 10;
+
+/// Lobbies without activity for this many minutes are left alone, their players are most likely gone
+const LOBBY_IDLE_MINUTES: i64 = 10;
+
+/// Puts the user into the fullest open public lobby of the language,
+/// or creates a new public session if there is none
+#[post("/join")]
+async fn join_matchmaking(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    matchmaking_body: OptionalJson<MatchmakingDto>,
+) -> Result<impl Responder, ApiError> {
+    let (language, nickname) = match matchmaking_body.into_inner() {
+        Some(matchmaking_body) => (matchmaking_body.language, matchmaking_body.nickname),
+        None => (None, None),
+    };
+
+    // stored the same way as nicknames given when joining directly
+    let nickname = nickname.map(|nickname| normalize_display_name(&nickname));
+
+    let language = language.unwrap_or(DEFAULT_LANGUAGE.to_string());
+
+    let token = lock_matchmaking(&ctx, &language).await?;
+
+    let result = find_or_create_lobby(&ctx, &user_id.0, &language, nickname).await;
+
+    if let Err(err) = ctx.cache.unlock_matchmaking(&language, &token).await {
+        log::error!("Could not release matchmaking lock: {}", err);
+    }
+
+    Ok(HttpResponse::Ok().json(result?))
+}
+
+async fn lock_matchmaking(ctx: &AppContext, language: &str) -> Result<String, ApiError> {
+    for _ in 0..LOCK_ATTEMPTS {
+        if let Some(token) = ctx.cache.lock_matchmaking(language).await? {
+            return Ok(token);
+        }
+
+        actix::clock::sleep(LOCK_RETRY_DELAY).await;
+    }
+
+    Err(ApiError::TooManyRequests {
+        message: "Matchmaking is busy, please try again".to_string(),
+        retry_after: 1,
+    })
+}
+
+async fn find_or_create_lobby(
+    ctx: &AppContext,
+    user_id: &str,
+    language: &str,
+    nickname: Option<String>,
+) -> Result<SessionMatch, ApiError> {
+    let lobbies = ctx
+        .db
+        .get_public_sessions(SessionFilter {
+            phase: Some(SessionPhase::Lobby),
+            language: Some(language.to_string()),
+            free_slots: true,
+            active_since: Some(
+                Utc::now().naive_utc() - chrono::Duration::minutes(LOBBY_IDLE_MINUTES),
+            ),
+            sort: SessionSort::Players,
+            limit: LOBBY_CANDIDATES,
+            ..Default::default()
+        })
+        .await?;
+
+    let online_players = ctx
+        .cache
+        .count_set_members(
+            lobbies
+                .iter()
+                .map(|(session, _)| Cache::session_users_key(&session.id))
+                .collect(),
+        )
+        .await?;
+
+    let open_lobby = ctx.cache.get_open_lobby(language).await?;
+
+    // the lobby quick play is filling may not have anyone connected yet
+    let is_open_lobby = |session: &Session| Some(&session.id) == open_lobby.as_ref();
+
+    let lobby = lobbies
+        .iter()
+        .zip(online_players)
+        .filter(|((session, _), online_players)| *online_players > 0 || is_open_lobby(session))
+        .map(|((session, _), _)| session)
+        .reduce(|fullest, session| match is_open_lobby(session) {
+            true => session,
+            false => fullest,
+        })
+        .map(|session| session.id.clone());
+
+    if let Some(lobby) = lobby {
+        let already_waitlisted = ctx
+            .db
+            .get_waitlist_position(&lobby, user_id)
+            .await?
+            .is_some();
+
+        match ctx.db.join_session(&lobby, user_id, nickname.clone()).await {
+            Ok(SessionJoin::Joined(session)) => {
+                return Ok(SessionMatch {
+                    session,
+                    created: false,
+                    waitlist_position: None,
+                })
+            }
+            Err(ApiError::UniqueViolation { .. }) => {
+                return Ok(SessionMatch {
+                    session: ctx.db.get_session_by_id(&lobby).await?,
+                    created: false,
+                    waitlist_position: None,
+                })
+            }
+            // a place the user queued for themselves before is kept
+            Ok(SessionJoin::Waitlisted { session, position }) if already_waitlisted => {
+                return Ok(SessionMatch {
+                    session,
+                    created: false,
+                    waitlist_position: Some(position),
+                })
+            }
+            // filled up by someone joining directly, quick play doesn't wait
+            Ok(SessionJoin::Waitlisted { session, .. }) => {
+                ctx.db.remove_from_session(&session.id, user_id).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let session = ctx
+        .db
+        .create_session_with_generated_id(NewSession {
+            id: String::new(),
+            public: true,
+            admin_user_id: user_id.to_string(),
+            content_filter: Strictness::default().to_string(),
+            language: language.to_string(),
+            max_players: envs::max_players_per_session(),
+        })
+        .await?;
+
+    if nickname.is_some() {
+        ctx.db.set_nickname(&session.id, user_id, nickname).await?;
+    }
+
+    ctx.cache.set_open_lobby(language, &session.id).await?;
+
+    Ok(SessionMatch {
+        session,
+        created: true,
+        waitlist_position: None,
+    })
+}
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(web::scope("/matchmaking").service(join_matchmaking));
+}
@@ -1,16 +1,54 @@
 mod auth;
+mod device_links;
+mod matchmaking;
 mod sessions;
+mod users;
+mod word_packs;
 mod ws;
 
-use actix_web::web;
+use std::time::Duration;
+
+use actix_web::{http::Method, web};
 pub use auth::SESSION_USER_ID;
 
+use crate::middlewares::rate_limit::RateLimiter;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+fn api_rate_limiter() -> RateLimiter {
+    RateLimiter::new("api", 120, RATE_LIMIT_WINDOW)
+        .route(Method::POST, "/api/auth", 10, RATE_LIMIT_WINDOW)
+        .route(Method::POST, "/api/auth/register", 5, RATE_LIMIT_WINDOW)
+        .route(Method::POST, "/api/auth/login", 10, RATE_LIMIT_WINDOW)
+        .route(Method::POST, "/api/auth/token", 20, RATE_LIMIT_WINDOW)
+        .route(Method::POST, "/api/auth/links", 10, RATE_LIMIT_WINDOW)
+        .route(
+            Method::POST,
+            "/api/auth/links/redeem",
+            10,
+            RATE_LIMIT_WINDOW,
+        )
+        .route(Method::POST, "/api/sessions", 20, RATE_LIMIT_WINDOW)
+        .route(Method::POST, "/api/matchmaking/join", 20, RATE_LIMIT_WINDOW)
+        .route(
+            Method::POST,
+            "/api/sessions/{session_id}/words",
+            30,
+            RATE_LIMIT_WINDOW,
+        )
+}
+
 pub fn config(config: &mut web::ServiceConfig) {
     config
         .service(
             web::scope("/api")
+                .wrap(api_rate_limiter())
                 .configure(sessions::config)
-                .configure(auth::config),
+                .configure(auth::config)
+                .configure(device_links::config)
+                .configure(matchmaking::config)
+                .configure(users::config)
+                .configure(word_packs::config),
         )
         .configure(ws::config);
 }
@@ -1,63 +1,184 @@
+use actix::Addr;
 use actix_web::{
-    get, post,
+    delete, get, patch, post, put,
     web::{self, Data, Path},
     HttpResponse, Responder, Result,
 };
-use actix_web_validator::Json;
+use actix_web_validator::{Json, Query};
 
 use crate::{
-    extractors::user_id::UserId,
+    extractors::{optional_json::OptionalJson, optional_user_id::OptionalUserId, user_id::UserId},
     models::{
         custom_api_errors::ApiError,
-        dtos::{session::NewSessionDto, word::NewWordDto},
-        session::{NewSession, SessionInfoPersonal},
+        dtos::{
+            chat::ChatHistoryQuery,
+            session::{
+                JoinSessionDto, NewSessionDto, NicknameDto, RematchDto, SessionsQuery,
+                UpdateSessionDto,
+            },
+            word::NewWordDto,
+            word_pack::SessionWordPacksDto,
+        },
+        session::{
+            NewSession, SessionCursor, SessionFilter, SessionIdAvailability, SessionInfoPersonal,
+            SessionJoin, SessionListing, SessionPage, SessionPhase, SessionUpdate,
+            SessionWaitlisted, DEFAULT_LANGUAGE,
+        },
         word::NewWord,
     },
-    AppContext,
+    repositories::cache::Cache,
+    utils::{envs, session_ids::invalid_session_id, validators::normalize_display_name},
+    websocket::{server::CharadeServer, MembersChanged, RematchCreated, UserUpdated},
+    AppContext, Response,
 };
 
+const DEFAULT_CHAT_PAGE_SIZE: i64 = 50;
+
+const DEFAULT_SESSIONS_PAGE_SIZE: i64 = 20;
+
 #[post("")]
 async fn create_session(
     ctx: Data<AppContext>,
     new_session: Json<NewSessionDto>,
     user_id: UserId,
 ) -> Result<impl Responder, ApiError> {
+    let new_session_id = new_session.id.clone();
+
     let new_session = NewSession {
-        id: new_session.id.clone(),
+        id: new_session_id.clone().unwrap_or_default(),
         public: new_session.public.unwrap_or(false),
         admin_user_id: user_id.0,
+        content_filter: new_session.content_filter.unwrap_or_default().to_string(),
+        language: new_session
+            .language
+            .clone()
+            .unwrap_or(DEFAULT_LANGUAGE.to_string()),
+        max_players: new_session
+            .max_players
+            .unwrap_or_else(envs::max_players_per_session),
     };
 
-    let created_session = ctx
-        .db
-        .create_session(new_session)
-        .await
-        .map_err(|e| match e {
-            ApiError::UniqueViolation { .. } => ApiError::UniqueViolation {
-                message: "Session already exists".to_string(),
-            },
-            _ => e,
-        })?;
+    let created_session = match new_session_id {
+        Some(id) => ctx
+            .db
+            .create_session(new_session)
+            .await
+            .map_err(|e| match e {
+                ApiError::UniqueViolation { .. } => ApiError::UniqueViolation {
+                    message: format!("Session id '{}' is already taken", id),
+                },
+                _ => e,
+            })?,
+        None => ctx.db.create_session_with_generated_id(new_session).await?,
+    };
 
     Ok(HttpResponse::Ok().json(created_session))
 }
 
+/// Public sessions, filtered and paginated with the cursor of the previous page
 #[get("")]
-async fn get_sessions(ctx: Data<AppContext>) -> Result<impl Responder, ApiError> {
-    let sessions = ctx.db.get_sessions().await?;
-    Ok(HttpResponse::Ok().json(sessions))
+async fn get_sessions(
+    ctx: Data<AppContext>,
+    query: Query<SessionsQuery>,
+) -> Result<impl Responder, ApiError> {
+    let query = query.into_inner();
+    let limit = query.limit.unwrap_or(DEFAULT_SESSIONS_PAGE_SIZE);
+
+    let cursor = query
+        .cursor
+        .map(|cursor| cursor.parse::<SessionCursor>())
+        .transpose()
+        .map_err(|_| ApiError::BadRequest {
+            message: "Invalid cursor".to_string(),
+        })?;
+
+    let filter = SessionFilter {
+        phase: query.phase,
+        language: query.language,
+        free_slots: query.free_slots.unwrap_or(false),
+        created_since: query.created_since,
+        active_since: None,
+        id_prefix: query.search.map(|search| search.trim().to_lowercase()),
+        sort: query.sort,
+        cursor,
+        // one more to know if there is a next page
+        limit: limit + 1,
+    };
+
+    let mut sessions = ctx.db.get_public_sessions(filter).await?;
+
+    let has_more = sessions.len() as i64 > limit;
+    sessions.truncate(limit as usize);
+
+    let online_players = ctx
+        .cache
+        .count_set_members(
+            sessions
+                .iter()
+                .map(|(session, _)| Cache::session_users_key(&session.id))
+                .collect(),
+        )
+        .await?;
+
+    let sessions: Vec<SessionListing> = sessions
+        .into_iter()
+        .zip(online_players)
+        .map(
+            |((session, number_of_players), online_players)| SessionListing {
+                session,
+                number_of_players,
+                online_players,
+            },
+        )
+        .collect();
+
+    let next_cursor = sessions
+        .last()
+        .filter(|_| has_more)
+        .map(|listing| listing.cursor(query.sort).to_string());
+
+    Ok(HttpResponse::Ok().json(SessionPage {
+        sessions,
+        next_cursor,
+    }))
 }
 
 #[get("/personal")]
 async fn get_all_personal_sessions(
-    user_id: UserId,
+    user_id: OptionalUserId,
     ctx: Data<AppContext>,
 ) -> Result<impl Responder, ApiError> {
-    let sessions = ctx.db.get_sessions_by_user(&user_id.0).await?;
+    let sessions = match user_id.0 {
+        Some(user_id) => ctx.db.get_sessions_by_user(&user_id).await?,
+        None => vec![],
+    };
 
     Ok(HttpResponse::Ok().json(sessions))
 }
 
+/// Whether a session can be created with this id
+#[get("/{session_id}/availability")]
+async fn get_session_id_availability(
+    ctx: Data<AppContext>,
+    session_id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let id = session_id.into_inner();
+
+    let reason = match invalid_session_id(&id) {
+        Some((reason, _)) => Some(reason),
+        None if ctx.db.session_exists(&id).await? => {
+            Some(format!("Session id '{}' is already taken", id))
+        }
+        None => None,
+    };
+
+    Ok(HttpResponse::Ok().json(SessionIdAvailability {
+        id,
+        available: reason.is_none(),
+        reason,
+    }))
+}
+
 #[get("/{session_id}")]
 async fn get_session(
     session_id: Path<String>,
@@ -67,6 +188,57 @@ async fn get_session(
     Ok(HttpResponse::Ok().json(session))
 }
 
+/// Changes the settings of the session, admin only
+#[patch("/{session_id}")]
+async fn update_session(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+    session_id: Path<String>,
+    session_body: Json<UpdateSessionDto>,
+) -> Result<impl Responder, ApiError> {
+    let session = ctx.db.get_session_by_id(&session_id).await?;
+
+    if session.admin_user_id != user_id.0 {
+        return Err(ApiError::Unauthorized {
+            message: "Only the admin can change the session".to_string(),
+        });
+    }
+
+    let session_body = session_body.into_inner();
+
+    let update = SessionUpdate {
+        public: session_body.public,
+        content_filter: session_body
+            .content_filter
+            .map(|strictness| strictness.to_string()),
+        language: session_body.language,
+        max_players: session_body.max_players,
+    };
+
+    if update.is_empty() {
+        return Ok(HttpResponse::Ok().json(session));
+    }
+
+    let raised_limit = update
+        .max_players
+        .is_some_and(|max_players| max_players > session.max_players);
+
+    let session = ctx.db.update_session(&session.id, update).await?;
+
+    if raised_limit {
+        let promoted = ctx.db.promote_waitlisted_users(&session.id).await?;
+
+        srv.do_send(MembersChanged {
+            session_id: session.id.clone(),
+            kicked: None,
+            promoted,
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(session))
+}
+
 #[get("/{session_id}/personal")]
 async fn get_personal_session(
     session_id: Path<String>,
@@ -89,18 +261,26 @@ async fn get_personal_session(
     }))
 }
 
+/// Joins the session, users joining a full session are put on its waitlist
 #[post("/{session_id}/join")]
 async fn join_session(
     user_id: UserId,
     ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
     session_id: Path<String>,
+    join_body: OptionalJson<JoinSessionDto>,
 ) -> Result<impl Responder, ApiError> {
     let user_id_clone = user_id.clone();
     let session_id_clone = session_id.clone();
     let db_cloned = ctx.db.clone();
 
+    let nickname = join_body
+        .into_inner()
+        .and_then(|join_body| join_body.nickname)
+        .map(|nickname| normalize_display_name(&nickname));
+
     let result = db_cloned
-        .join_session(&session_id_clone, &user_id_clone.0)
+        .join_session(&session_id_clone, &user_id_clone.0, nickname.clone())
         .await
         .map_err(|e| match e {
             ApiError::NotFound { .. } | ApiError::ForeignKeyViolation { .. } => {
@@ -113,13 +293,245 @@ async fn join_session(
 
     match result {
         Err(ApiError::UniqueViolation { .. }) => {
+            if nickname.is_some() {
+                ctx.db
+                    .set_nickname(&session_id, &user_id.0, nickname)
+                    .await?;
+            }
+
             let session = ctx.db.get_session_by_id(&session_id).await?;
 
             Ok(HttpResponse::Ok().json(session))
         }
         Err(e) => Err(e),
-        Ok(session) => Ok(HttpResponse::Ok().json(session)),
+        Ok(SessionJoin::Joined(session)) => Ok(HttpResponse::Ok().json(session)),
+        Ok(SessionJoin::Waitlisted { session, position }) => {
+            srv.do_send(MembersChanged {
+                session_id: session.id.clone(),
+                kicked: None,
+                promoted: vec![],
+            });
+
+            Ok(HttpResponse::Accepted().json(SessionWaitlisted {
+                session,
+                waitlist_position: position,
+            }))
+        }
+    }
+}
+
+/// Creates a new session with the same members, settings and word packs, admin only.
+/// Connected clients are told to move over to it.
+#[post("/{session_id}/rematch")]
+async fn create_rematch(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+    session_id: Path<String>,
+    rematch_body: OptionalJson<RematchDto>,
+) -> Result<impl Responder, ApiError> {
+    let session = ctx.db.get_session_by_id(&session_id).await?;
+
+    if session.admin_user_id != user_id.0 {
+        return Err(ApiError::Unauthorized {
+            message: "Only the admin can start a rematch".to_string(),
+        });
+    }
+
+    if session.phase() == SessionPhase::Playing {
+        return Err(ApiError::BadRequest {
+            message: "The game is still running".to_string(),
+        });
+    }
+
+    let keep_words = rematch_body
+        .into_inner()
+        .map(|rematch_body| rematch_body.keep_words)
+        .unwrap_or(false);
+
+    let rematch = ctx.db.create_rematch(&session, keep_words).await?;
+
+    srv.do_send(RematchCreated {
+        session_id: session.id,
+        new_session_id: rematch.id.clone(),
+    });
+
+    Ok(HttpResponse::Ok().json(rematch))
+}
+
+/// Leaves the session or its waitlist, the next waitlisted user takes the free slot
+#[post("/{session_id}/leave")]
+async fn leave_session(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+    session_id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let session = ctx.db.get_session_by_id(&session_id).await?;
+
+    if session.admin_user_id == user_id.0 {
+        return Err(ApiError::BadRequest {
+            message: "The admin can't leave the session".to_string(),
+        });
     }
+
+    let promoted = ctx.db.remove_from_session(&session.id, &user_id.0).await?;
+
+    srv.do_send(MembersChanged {
+        session_id: session.id,
+        kicked: None,
+        promoted,
+    });
+
+    Ok(HttpResponse::Ok().json(Response {
+        message: "Left session".to_string(),
+    }))
+}
+
+/// Removes a member or waitlisted user, admin only
+#[delete("/{session_id}/members/{member_id}")]
+async fn kick_member(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+    path: Path<(String, String)>,
+) -> Result<impl Responder, ApiError> {
+    let (session_id, member_id) = path.into_inner();
+
+    let session = ctx.db.get_session_by_id(&session_id).await?;
+
+    if session.admin_user_id != user_id.0 {
+        return Err(ApiError::Unauthorized {
+            message: "Only the admin can kick members".to_string(),
+        });
+    }
+
+    if member_id == user_id.0 {
+        return Err(ApiError::BadRequest {
+            message: "The admin can't kick themselves".to_string(),
+        });
+    }
+
+    let promoted = ctx.db.remove_from_session(&session.id, &member_id).await?;
+
+    srv.do_send(MembersChanged {
+        session_id: session.id,
+        kicked: Some(member_id),
+        promoted,
+    });
+
+    Ok(HttpResponse::Ok().json(Response {
+        message: "Member removed".to_string(),
+    }))
+}
+
+#[put("/{session_id}/nickname")]
+async fn set_nickname(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    srv: Data<Addr<CharadeServer>>,
+    session_id: Path<String>,
+    nickname_body: Json<NicknameDto>,
+) -> Result<impl Responder, ApiError> {
+    let nickname = nickname_body
+        .into_inner()
+        .nickname
+        .map(|nickname| normalize_display_name(&nickname));
+
+    ctx.db
+        .set_nickname(&session_id, &user_id.0, nickname)
+        .await?;
+
+    srv.do_send(UserUpdated { id: user_id.0 });
+
+    Ok(HttpResponse::Ok().json(Response {
+        message: "Nickname updated".to_string(),
+    }))
+}
+
+/// Chat history, newest page first. Older pages are loaded with `before`.
+#[get("/{session_id}/chat")]
+async fn get_chat_messages(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    session_id: Path<String>,
+    query: Query<ChatHistoryQuery>,
+) -> Result<impl Responder, ApiError> {
+    if !ctx.db.is_session_member(&session_id, &user_id.0).await? {
+        return Err(ApiError::NotFound {
+            message: "You are not a member of this session".to_string(),
+        });
+    }
+
+    let page = ctx
+        .db
+        .get_chat_messages(
+            &session_id,
+            query.before,
+            query.limit.unwrap_or(DEFAULT_CHAT_PAGE_SIZE),
+        )
+        .await?;
+
+    Ok(HttpResponse::Ok().json(page))
+}
+
+/// Recap of the last finished game of the session
+#[get("/{session_id}/summary")]
+async fn get_game_summary(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    session_id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    if !ctx.db.is_session_member(&session_id, &user_id.0).await? {
+        return Err(ApiError::NotFound {
+            message: "You are not a member of this session".to_string(),
+        });
+    }
+
+    let summary = ctx
+        .db
+        .get_game_summary(&session_id)
+        .await?
+        .ok_or(ApiError::NotFound {
+            message: "No game of this session has finished yet".to_string(),
+        })?;
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+#[get("/{session_id}/word-packs")]
+async fn get_session_word_packs(
+    ctx: Data<AppContext>,
+    session_id: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let packs = ctx.db.get_session_word_packs(&session_id).await?;
+
+    Ok(HttpResponse::Ok().json(packs))
+}
+
+/// Selects the word packs used to top up the words of the session, admin only
+#[put("/{session_id}/word-packs")]
+async fn set_session_word_packs(
+    user_id: UserId,
+    ctx: Data<AppContext>,
+    session_id: Path<String>,
+    packs_body: Json<SessionWordPacksDto>,
+) -> Result<impl Responder, ApiError> {
+    let session = ctx.db.get_session_by_id(&session_id).await?;
+
+    if session.admin_user_id != user_id.0 {
+        return Err(ApiError::Unauthorized {
+            message: "Only the admin can select word packs".to_string(),
+        });
+    }
+
+    ctx.db
+        .set_session_word_packs(&session.id, packs_body.into_inner().pack_ids)
+        .await?;
+
+    let packs = ctx.db.get_session_word_packs(&session.id).await?;
+
+    Ok(HttpResponse::Ok().json(packs))
 }
 
 #[post("/{session_id}/words")]
@@ -129,11 +541,7 @@ async fn add_word_to_session(
     new_word: Json<NewWordDto>,
     user_id: UserId,
 ) -> Result<impl Responder, ApiError> {
-    let new_word = NewWord {
-        word: new_word.word.to_owned(),
-        session_id: session_id.to_owned(),
-        user_id: user_id.into(),
-    };
+    let new_word = NewWord::new(&new_word.word, &session_id, &user_id.0);
 
     let word = ctx.db.add_word_to_session(new_word).await?;
 
@@ -146,9 +554,19 @@ pub fn config(config: &mut web::ServiceConfig) {
             .service(create_session)
             .service(get_sessions)
             .service(get_all_personal_sessions)
+            .service(get_session_id_availability)
             .service(get_session)
+            .service(update_session)
             .service(get_personal_session)
             .service(join_session)
+            .service(create_rematch)
+            .service(leave_session)
+            .service(kick_member)
+            .service(set_nickname)
+            .service(get_chat_messages)
+            .service(get_game_summary)
+            .service(get_session_word_packs)
+            .service(set_session_word_packs)
             .service(add_word_to_session),
     );
 }
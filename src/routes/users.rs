@@ -0,0 +1,27 @@
+use actix_web::{
+    get,
+    web::{self, Data, Path},
+    HttpResponse, Responder,
+};
+
+use crate::{
+    extractors::user_id::UserId, models::custom_api_errors::ApiError, utils::stats, AppContext,
+};
+
+/// Stats are visible to every signed in user, like the names in a session
+#[get("/{user_id}/stats")]
+async fn get_user_stats(
+    _user_id: UserId,
+    ctx: Data<AppContext>,
+    path: Path<String>,
+) -> Result<impl Responder, ApiError> {
+    let user = ctx.db.get_user_by_id(&path).await?;
+
+    let stats = stats::user_stats(&ctx.db, &ctx.cache, &user.id).await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(web::scope("/users").service(get_user_stats));
+}
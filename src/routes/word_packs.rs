@@ -0,0 +1,25 @@
+use actix_web::{
+    get,
+    web::{self, Data},
+    HttpResponse, Responder,
+};
+
+use crate::{
+    models::{custom_api_errors::ApiError, dtos::word_pack::WordPacksQuery},
+    AppContext,
+};
+
+/// All word packs, optionally only those of one `language`
+#[get("")]
+async fn get_word_packs(
+    ctx: Data<AppContext>,
+    query: web::Query<WordPacksQuery>,
+) -> Result<impl Responder, ApiError> {
+    let packs = ctx.db.get_word_packs(query.language.as_deref()).await?;
+
+    Ok(HttpResponse::Ok().json(packs))
+}
+
+pub fn config(config: &mut web::ServiceConfig) {
+    config.service(web::scope("/word-packs").service(get_word_packs));
+}
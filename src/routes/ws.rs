@@ -9,8 +9,15 @@ use actix_web_actors::ws;
 use serde::Deserialize;
 
 use crate::{
+    extractors::user_id::UserId,
     models::custom_api_errors::ApiError,
-    websocket::{server, session},
+    routes::SESSION_USER_ID,
+    utils::tokens::verify_access_token,
+    websocket::{
+        encoding::Encoding,
+        rate_limiter::{RateLimiter, RateLimits},
+        server, session,
+    },
     AppContext,
 };
 
@@ -21,8 +28,11 @@ struct ConnectWebsocketPathParams {
 }
 
 #[derive(Debug, Deserialize)]
-struct UserTokenBody {
-    user_id: String,
+struct ConnectWebsocketQueryParams {
+    #[serde(default)]
+    encoding: Encoding,
+    /// browsers can't set headers on websocket upgrades, so the access token can be passed here
+    access_token: Option<String>,
 }
 
 #[get("/ws/{session_id}/{user_cookie}")]
@@ -32,6 +42,7 @@ async fn connect_websocket(
     srv: web::Data<Addr<server::CharadeServer>>,
     ctx: web::Data<AppContext>,
     path_params: web::Path<ConnectWebsocketPathParams>,
+    query_params: web::Query<ConnectWebsocketQueryParams>,
 ) -> Result<HttpResponse, actix_web::Error> {
     let cookie = Cookie::parse(format!("token={}", path_params.user_cookie.clone())).map_err(
         |_: actix_web::cookie::ParseError| {
@@ -45,27 +56,102 @@ async fn connect_websocket(
 
     let secret_key = Key::from(ctx.cookie_secret.as_bytes());
 
-    let cookie_json_string = jar.private(&secret_key).get("token").ok_or_else(|| {
+    // the private cookie only holds the key of the session state stored in the cache
+    let session_key = jar.private(&secret_key).get("token").ok_or_else(|| {
         actix_web::Error::from(ApiError::internal("Could not decrypt cookie".to_string()))
     })?;
 
-    let cookie_json = serde_json::from_str::<UserTokenBody>(cookie_json_string.value())?;
+    let user_id = ctx
+        .cache
+        .get_auth_session(session_key.value())
+        .await?
+        .and_then(|state| state.get(SESSION_USER_ID).cloned())
+        .ok_or(ApiError::Unauthorized {
+            message: "Please authenticate first".to_string(),
+        })?;
+
+    let user_id = serde_json::from_str::<String>(&user_id)?;
+
+    start_session(
+        &req,
+        stream,
+        &srv,
+        &ctx,
+        user_id,
+        path_params.session_id.clone(),
+        query_params.encoding,
+    )
+    .await
+}
+
+/// Accepts an access token (header or `access_token` query param) or the session cookie
+#[get("/ws/{session_id}")]
+async fn connect_websocket_authenticated(
+    req: HttpRequest,
+    stream: web::Payload,
+    srv: web::Data<Addr<server::CharadeServer>>,
+    ctx: web::Data<AppContext>,
+    session_id: web::Path<String>,
+    query_params: web::Query<ConnectWebsocketQueryParams>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let user_id = match &query_params.access_token {
+        Some(token) => verify_access_token(token)?,
+        None => UserId::extract(&req)?.0,
+    };
+
+    start_session(
+        &req,
+        stream,
+        &srv,
+        &ctx,
+        user_id,
+        session_id.into_inner(),
+        query_params.encoding,
+    )
+    .await
+}
+
+/// Only members and waitlisted users can connect, the latter just get their waitlist position
+async fn start_session(
+    req: &HttpRequest,
+    stream: web::Payload,
+    srv: &Addr<server::CharadeServer>,
+    ctx: &AppContext,
+    user_id: String,
+    session_id: String,
+    encoding: Encoding,
+) -> Result<HttpResponse, actix_web::Error> {
+    let is_member = ctx.db.is_session_member(&session_id, &user_id).await?;
 
-    let user_id = serde_json::from_str::<String>(&cookie_json.user_id)?;
+    if !is_member
+        && ctx
+            .db
+            .get_waitlist_position(&session_id, &user_id)
+            .await?
+            .is_none()
+    {
+        return Err(ApiError::NotFound {
+            message: "You are not a member of this session".to_string(),
+        }
+        .into());
+    }
 
     ws::start(
         session::WsCharadeSession {
             id: user_id,
-            session_id: path_params.session_id.clone(),
+            session_id,
             hb: Instant::now(),
-            server: srv.get_ref().clone(),
-            db: ctx.db.clone(),
+            server: srv.clone(),
+            encoding,
+            rate_limiter: RateLimiter::new(RateLimits::configured()),
         },
-        &req,
+        req,
         stream,
     )
 }
 
 pub fn config(config: &mut web::ServiceConfig) {
-    config.service(connect_websocket);
+    config
+        .service(connect_websocket)
+        .service(connect_websocket_authenticated);
 }
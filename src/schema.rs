@@ -1,5 +1,69 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    chat_messages (id) {
+        id -> Int8,
+        #[max_length = 20]
+        session_id -> Varchar,
+        #[max_length = 36]
+        user_id -> Varchar,
+        #[max_length = 500]
+        message -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    game_players (game_id, user_id) {
+        game_id -> Uuid,
+        #[max_length = 36]
+        user_id -> Varchar,
+        score -> Int4,
+        won -> Bool,
+        words_performed -> Int4,
+        words_guessed -> Int4,
+        guesses -> Int4,
+        perform_time_ms -> Int8,
+    }
+}
+
+diesel::table! {
+    game_words (game_id, position) {
+        game_id -> Uuid,
+        position -> Int4,
+        #[max_length = 255]
+        word -> Varchar,
+        #[max_length = 36]
+        performed_by -> Nullable<Varchar>,
+        #[max_length = 36]
+        guessed_by -> Nullable<Varchar>,
+        duration_ms -> Int8,
+        skips -> Int4,
+    }
+}
+
+diesel::table! {
+    games (id) {
+        id -> Uuid,
+        #[max_length = 20]
+        session_id -> Varchar,
+        started_at -> Timestamp,
+        finished_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    session_waitlist (session_id, user_id) {
+        #[max_length = 20]
+        session_id -> Varchar,
+        #[max_length = 36]
+        user_id -> Varchar,
+        #[max_length = 100]
+        nickname -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     sessions (id) {
         #[max_length = 20]
@@ -8,6 +72,23 @@ diesel::table! {
         created_at -> Timestamp,
         #[max_length = 36]
         admin_user_id -> Varchar,
+        #[max_length = 10]
+        content_filter -> Varchar,
+        #[max_length = 16]
+        language -> Varchar,
+        #[max_length = 10]
+        phase -> Varchar,
+        last_activity_at -> Timestamp,
+        max_players -> Int4,
+    }
+}
+
+diesel::table! {
+    sessions_word_packs (session_id, pack_id) {
+        #[max_length = 20]
+        session_id -> Varchar,
+        #[max_length = 50]
+        pack_id -> Varchar,
     }
 }
 
@@ -15,9 +96,21 @@ diesel::table! {
     users (id) {
         #[max_length = 36]
         id -> Varchar,
-        #[max_length = 50]
+        #[max_length = 100]
         name -> Nullable<Varchar>,
         created_at -> Timestamp,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        #[max_length = 120]
+        username -> Nullable<Varchar>,
+        #[max_length = 255]
+        password_hash -> Nullable<Varchar>,
+        #[max_length = 7]
+        avatar_color -> Nullable<Varchar>,
+        #[max_length = 32]
+        avatar_emoji -> Nullable<Varchar>,
+        #[max_length = 16]
+        locale -> Nullable<Varchar>,
     }
 }
 
@@ -27,6 +120,8 @@ diesel::table! {
         user_id -> Varchar,
         #[max_length = 20]
         session_id -> Varchar,
+        #[max_length = 100]
+        nickname -> Nullable<Varchar>,
     }
 }
 
@@ -39,18 +134,80 @@ diesel::table! {
         session_id -> Varchar,
         #[max_length = 36]
         user_id -> Varchar,
+        #[max_length = 255]
+        normalized -> Varchar,
+        similarity_key -> Text,
+    }
+}
+
+diesel::table! {
+    words_before_normalization (word, session_id) {
+        #[max_length = 255]
+        word -> Varchar,
+        created_at -> Timestamp,
+        #[max_length = 20]
+        session_id -> Varchar,
+        #[max_length = 36]
+        user_id -> Varchar,
+        #[max_length = 255]
+        rewritten_to -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    word_pack_words (pack_id, word) {
+        #[max_length = 50]
+        pack_id -> Varchar,
+        #[max_length = 255]
+        word -> Varchar,
+    }
+}
+
+diesel::table! {
+    word_packs (id) {
+        #[max_length = 50]
+        id -> Varchar,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 50]
+        category -> Varchar,
+        created_at -> Timestamp,
+        #[max_length = 16]
+        language -> Varchar,
     }
 }
 
+diesel::joinable!(chat_messages -> sessions (session_id));
+diesel::joinable!(chat_messages -> users (user_id));
+diesel::joinable!(game_players -> games (game_id));
+diesel::joinable!(game_players -> users (user_id));
+diesel::joinable!(game_words -> games (game_id));
+diesel::joinable!(games -> sessions (session_id));
+diesel::joinable!(session_waitlist -> sessions (session_id));
+diesel::joinable!(session_waitlist -> users (user_id));
 diesel::joinable!(sessions -> users (admin_user_id));
+diesel::joinable!(sessions_word_packs -> sessions (session_id));
+diesel::joinable!(sessions_word_packs -> word_packs (pack_id));
 diesel::joinable!(users_sessions -> sessions (session_id));
 diesel::joinable!(users_sessions -> users (user_id));
+diesel::joinable!(word_pack_words -> word_packs (pack_id));
 diesel::joinable!(words -> sessions (session_id));
 diesel::joinable!(words -> users (user_id));
+diesel::joinable!(words_before_normalization -> sessions (session_id));
+diesel::joinable!(words_before_normalization -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    chat_messages,
+    game_players,
+    game_words,
+    games,
+    session_waitlist,
     sessions,
+    sessions_word_packs,
     users,
     users_sessions,
+    word_pack_words,
+    word_packs,
     words,
+    words_before_normalization,
 );
@@ -1,10 +1,29 @@
+use std::net::IpAddr;
+
 use dotenvy::dotenv;
 use lazy_static::lazy_static;
 
+/// 30 days
+const DEFAULT_SESSION_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+
+const DEFAULT_GUESS_MAX_DISTANCE: usize = 2;
+
+const DEFAULT_WORD_PACKS_DIR: &str = "word_packs";
+
+const DEFAULT_MAX_PLAYERS_PER_SESSION: i32 = 12;
+
 pub struct Environment {
     pub database_url: String,
     pub cookie_secret: String,
     pub redis_url: String,
+    pub token_secret: String,
+    pub session_ttl_secs: i64,
+    pub ws_rate_limits: Option<String>,
+    pub guess_max_distance: usize,
+    pub word_packs_dir: String,
+    pub content_blocklist_path: Option<String>,
+    pub max_players_per_session: i32,
+    pub trusted_proxies: Vec<IpAddr>,
 }
 
 impl Environment {
@@ -14,11 +33,65 @@ impl Environment {
         let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
         let cookie_secret = std::env::var("COOKIE_SECRET").expect("COOKIE_SECRET must be set");
         let redis_url = std::env::var("REDIS_URL").expect("REDIS_URL must be set");
+        let token_secret = std::env::var("TOKEN_SECRET").expect("TOKEN_SECRET must be set");
+        let session_ttl_secs = std::env::var("SESSION_TTL_SECONDS")
+            .ok()
+            .filter(|ttl| !ttl.is_empty())
+            .map(|ttl| ttl.parse().expect("SESSION_TTL_SECONDS must be a number"))
+            .unwrap_or(DEFAULT_SESSION_TTL_SECS);
+        let ws_rate_limits = std::env::var("WS_RATE_LIMITS").ok();
+        let guess_max_distance = std::env::var("GUESS_MAX_DISTANCE")
+            .ok()
+            .filter(|distance| !distance.is_empty())
+            .map(|distance| {
+                distance
+                    .parse()
+                    .expect("GUESS_MAX_DISTANCE must be a number")
+            })
+            .unwrap_or(DEFAULT_GUESS_MAX_DISTANCE);
+        let word_packs_dir = std::env::var("WORD_PACKS_DIR")
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or(DEFAULT_WORD_PACKS_DIR.to_string());
+        let content_blocklist_path = std::env::var("CONTENT_BLOCKLIST_PATH")
+            .ok()
+            .filter(|path| !path.is_empty());
+        let max_players_per_session = std::env::var("MAX_PLAYERS_PER_SESSION")
+            .ok()
+            .filter(|max| !max.is_empty())
+            .map(|max| {
+                max.parse()
+                    .expect("MAX_PLAYERS_PER_SESSION must be a number")
+            })
+            .unwrap_or(DEFAULT_MAX_PLAYERS_PER_SESSION);
+        let trusted_proxies = std::env::var("TRUSTED_PROXIES")
+            .ok()
+            .map(|proxies| {
+                proxies
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|proxy| !proxy.is_empty())
+                    .map(|proxy| {
+                        proxy
+                            .parse()
+                            .expect("TRUSTED_PROXIES must be a list of ip addresses")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
 
         Self {
             database_url,
             cookie_secret,
             redis_url,
+            token_secret,
+            session_ttl_secs,
+            ws_rate_limits,
+            guess_max_distance,
+            word_packs_dir,
+            content_blocklist_path,
+            max_players_per_session,
+            trusted_proxies,
         }
     }
 }
@@ -38,3 +111,35 @@ pub fn cookie_secret() -> String {
 pub fn redis_url() -> String {
     ENV.redis_url.clone()
 }
+
+pub fn token_secret() -> String {
+    ENV.token_secret.clone()
+}
+
+pub fn session_ttl_secs() -> i64 {
+    ENV.session_ttl_secs
+}
+
+pub fn ws_rate_limits() -> Option<String> {
+    ENV.ws_rate_limits.clone()
+}
+
+pub fn guess_max_distance() -> usize {
+    ENV.guess_max_distance
+}
+
+pub fn word_packs_dir() -> String {
+    ENV.word_packs_dir.clone()
+}
+
+pub fn content_blocklist_path() -> Option<String> {
+    ENV.content_blocklist_path.clone()
+}
+
+pub fn max_players_per_session() -> i32 {
+    ENV.max_players_per_session
+}
+
+pub fn trusted_proxies() -> &'static [IpAddr] {
+    &ENV.trusted_proxies
+}
@@ -0,0 +1,272 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::utils::envs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessResult {
+    Correct,
+    /// Not correct, but close enough to hint the guesser
+    Close,
+    Wrong,
+}
+
+/// Lowercase, without diacritics and punctuation, single spaces between words
+pub fn normalize_guess(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The word itself and its possible singulars, only the last word of a phrase is changed
+fn singular_forms(word: &str) -> Vec<String> {
+    let mut forms = vec![word.to_string()];
+
+    if word.chars().count() <= 3 {
+        return forms;
+    }
+
+    if let Some(stem) = word.strip_suffix("ies") {
+        forms.push(format!("{stem}y"));
+    }
+
+    if let Some(stem) = word.strip_suffix("es") {
+        forms.push(stem.to_string());
+    }
+
+    if let Some(stem) = word.strip_suffix('s') {
+        forms.push(stem.to_string());
+    }
+
+    forms
+}
+
+/// The word itself and its possible plurals, the counterpart of [`singular_forms`]
+fn plural_forms(word: &str) -> Vec<String> {
+    let mut forms = vec![word.to_string(), format!("{word}s"), format!("{word}es")];
+
+    if let Some(stem) = word.strip_suffix('y') {
+        forms.push(format!("{stem}ies"));
+    }
+
+    forms
+}
+
+/// Similarity keys of the words that only differ from the word in case, diacritics,
+/// punctuation or plural. Unlike guesses, no typos are tolerated.
+pub fn near_duplicate_keys(word: &str) -> Vec<String> {
+    let mut keys: Vec<String> = singular_forms(&normalize_guess(word))
+        .iter()
+        .flat_map(|singular| plural_forms(singular))
+        .collect();
+
+    keys.sort();
+    keys.dedup();
+
+    keys
+}
+
+/// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Typos allowed for a word, short words have to be exact
+fn tolerance(word: &str, max_distance: usize) -> usize {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => max_distance.min(1),
+        _ => max_distance,
+    }
+}
+
+pub fn check_guess(guess: &str, secret: &str) -> GuessResult {
+    check_guess_within(guess, secret, envs::guess_max_distance())
+}
+
+/// [`check_guess`] with the typos allowed for long words given instead of `GUESS_MAX_DISTANCE`
+fn check_guess_within(guess: &str, secret: &str, max_distance: usize) -> GuessResult {
+    let guess = normalize_guess(guess);
+    let secret = normalize_guess(secret);
+
+    if guess.is_empty() {
+        return GuessResult::Wrong;
+    }
+
+    let tolerance = tolerance(&secret, max_distance);
+
+    let distance = singular_forms(&guess)
+        .iter()
+        .flat_map(|guess| {
+            singular_forms(&secret)
+                .into_iter()
+                .map(move |secret| edit_distance(guess, &secret))
+        })
+        .min()
+        .unwrap_or(usize::MAX);
+
+    if distance <= tolerance {
+        GuessResult::Correct
+    } else if distance <= tolerance + 2 {
+        GuessResult::Close
+    } else {
+        GuessResult::Wrong
+    }
+}
+
+/// Masks every part of the text that would count as a correct guess of the secret
+pub fn redact_secret(text: &str, secret: &str) -> String {
+    redact_secret_within(text, secret, envs::guess_max_distance())
+}
+
+fn redact_secret_within(text: &str, secret: &str, max_distance: usize) -> String {
+    let secret_words = normalize_guess(secret).split_whitespace().count();
+
+    let mut tokens: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+
+    if secret_words == 0 || tokens.len() < secret_words {
+        return text.to_string();
+    }
+
+    let mut redacted = false;
+
+    for start in 0..=(tokens.len() - secret_words) {
+        let window = tokens[start..start + secret_words].join(" ");
+
+        if check_guess_within(&window, secret, max_distance) == GuessResult::Correct {
+            for token in &mut tokens[start..start + secret_words] {
+                *token = "*".repeat(token.chars().count());
+            }
+
+            redacted = true;
+        }
+    }
+
+    // keeps the original whitespace if nothing had to be masked
+    if redacted {
+        tokens.join(" ")
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `DEFAULT_GUESS_MAX_DISTANCE`, the tests don't depend on the environment
+    const MAX_DISTANCE: usize = 2;
+
+    fn check(guess: &str, secret: &str) -> GuessResult {
+        check_guess_within(guess, secret, MAX_DISTANCE)
+    }
+
+    #[test]
+    fn normalizes_case_diacritics_and_punctuation() {
+        assert_eq!(normalize_guess("  Crème   Brûlée! "), "creme brulee");
+        assert_eq!(normalize_guess("Hide-and-Seek"), "hide and seek");
+        assert_eq!(normalize_guess("?!"), "");
+    }
+
+    #[test]
+    fn exact_guesses_are_correct() {
+        assert_eq!(check("elephant", "elephant"), GuessResult::Correct);
+        assert_eq!(check("ELEPHANT", "Elephant"), GuessResult::Correct);
+        assert_eq!(check("creme brulee", "Crème brûlée"), GuessResult::Correct);
+        assert_eq!(check("cat", "cat"), GuessResult::Correct);
+    }
+
+    #[test]
+    fn typos_are_tolerated_by_word_length() {
+        // long words allow the configured distance
+        assert_eq!(check("elefant", "elephant"), GuessResult::Correct);
+        // medium words allow one typo
+        assert_eq!(check("hourse", "horse"), GuessResult::Correct);
+        assert_eq!(check("hrse", "horse"), GuessResult::Correct);
+        // short words have to be exact
+        assert_eq!(check("cot", "cat"), GuessResult::Close);
+    }
+
+    #[test]
+    fn near_misses_are_close() {
+        assert_eq!(check("eleph", "elephant"), GuessResult::Close);
+        assert_eq!(check("hoe", "horse"), GuessResult::Close);
+        assert_eq!(check("ca", "cat"), GuessResult::Close);
+    }
+
+    #[test]
+    fn unrelated_guesses_are_wrong() {
+        assert_eq!(check("giraffe", "elephant"), GuessResult::Wrong);
+        assert_eq!(check("dog", "cat"), GuessResult::Wrong);
+        assert_eq!(check("", "cat"), GuessResult::Wrong);
+        assert_eq!(check("!!!", "cat"), GuessResult::Wrong);
+    }
+
+    #[test]
+    fn plurals_match_their_singular() {
+        assert_eq!(check("horses", "horse"), GuessResult::Correct);
+        assert_eq!(check("horse", "horses"), GuessResult::Correct);
+        assert_eq!(check("boxes", "box"), GuessResult::Correct);
+        assert_eq!(check("cities", "city"), GuessResult::Correct);
+        assert_eq!(check("city", "cities"), GuessResult::Correct);
+        assert_eq!(check("ice creams", "ice cream"), GuessResult::Correct);
+    }
+
+    #[test]
+    fn near_duplicates_only_differ_in_form() {
+        let keys = near_duplicate_keys("City");
+
+        assert!(keys.contains(&"city".to_string()));
+        assert!(keys.contains(&"cities".to_string()));
+        assert!(near_duplicate_keys("Bears").contains(&"bear".to_string()));
+        assert!(!near_duplicate_keys("bear").contains(&"beer".to_string()));
+    }
+
+    #[test]
+    fn redacts_the_secret_inside_longer_messages() {
+        assert_eq!(
+            redact_secret_within("is it an Elephant?", "elephant", MAX_DISTANCE),
+            "is it an *********"
+        );
+        assert_eq!(
+            redact_secret_within("I love ice cream so much", "ice cream", MAX_DISTANCE),
+            "I love *** ***** so much"
+        );
+        assert_eq!(
+            redact_secret_within("two elefants and an elephant", "elephant", MAX_DISTANCE),
+            "two ******** and an ********"
+        );
+    }
+
+    #[test]
+    fn keeps_messages_without_the_secret() {
+        assert_eq!(
+            redact_secret_within("no  idea   at all", "elephant", MAX_DISTANCE),
+            "no  idea   at all"
+        );
+        assert_eq!(
+            redact_secret_within("cat", "ice cream", MAX_DISTANCE),
+            "cat"
+        );
+        assert_eq!(redact_secret_within("a cot", "cat", MAX_DISTANCE), "a cot");
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+/// Language the messages are written in
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Catalogs map english messages to their translation, `{}` stands for a dynamic part
+const CATALOGS: &[(&str, &str)] = &[
+    ("de", include_str!("../../locales/de.json")),
+    ("ja", include_str!("../../locales/ja.json")),
+];
+
+struct Catalog {
+    messages: HashMap<String, String>,
+    /// entries with placeholders, longest first so the most specific one matches
+    patterns: Vec<(String, String)>,
+}
+
+impl Catalog {
+    fn parse(json: &str) -> Self {
+        let entries: HashMap<String, String> =
+            serde_json::from_str(json).expect("Invalid message catalog");
+
+        let (patterns, messages): (HashMap<_, _>, HashMap<_, _>) = entries
+            .into_iter()
+            .partition(|(message, _)| message.contains("{}"));
+
+        let mut patterns: Vec<(String, String)> = patterns.into_iter().collect();
+        patterns.sort_by_key(|(pattern, _)| std::cmp::Reverse(pattern.len()));
+
+        Self { messages, patterns }
+    }
+
+    fn translate(&self, message: &str) -> Option<String> {
+        if let Some(translation) = self.messages.get(message) {
+            return Some(translation.clone());
+        }
+
+        self.patterns.iter().find_map(|(pattern, translation)| {
+            let values = match_pattern(pattern, message)?;
+
+            let mut parts = translation.split("{}");
+            let mut translated = parts.next().unwrap_or_default().to_string();
+
+            for (value, part) in values.iter().zip(parts) {
+                translated.push_str(value);
+                translated.push_str(part);
+            }
+
+            Some(translated)
+        })
+    }
+}
+
+/// The values of the `{}` placeholders if the message matches the pattern
+fn match_pattern<'a>(pattern: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let parts: Vec<&str> = pattern.split("{}").collect();
+    let (first, rest) = parts.split_first()?;
+    let (last, middle) = rest.split_last()?;
+
+    let mut remaining = message.strip_prefix(first)?;
+    let mut values = vec![];
+
+    for part in middle {
+        let (value, after) = remaining.split_once(part)?;
+        values.push(value);
+        remaining = after;
+    }
+
+    values.push(remaining.strip_suffix(last)?);
+
+    Some(values)
+}
+
+lazy_static! {
+    static ref CATALOGS_BY_LANGUAGE: HashMap<&'static str, Catalog> = CATALOGS
+        .iter()
+        .map(|(language, json)| (*language, Catalog::parse(json)))
+        .collect();
+}
+
+/// true if messages can be translated to the language, english included
+pub fn is_supported(language: &str) -> bool {
+    language == DEFAULT_LANGUAGE || CATALOGS_BY_LANGUAGE.contains_key(language)
+}
+
+/// Translates the message, messages without a translation stay english
+pub fn translate(language: &str, message: &str) -> String {
+    CATALOGS_BY_LANGUAGE
+        .get(language)
+        .and_then(|catalog| catalog.translate(message))
+        .unwrap_or_else(|| message.to_string())
+}
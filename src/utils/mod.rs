@@ -1,2 +1,10 @@
+pub mod emoji;
 pub mod envs;
+pub mod guesses;
+pub mod i18n;
+pub mod moderation;
+pub mod passwords;
+pub mod session_ids;
+pub mod stats;
+pub mod tokens;
 pub mod validators;
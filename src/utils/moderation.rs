@@ -0,0 +1,224 @@
+use std::{collections::HashSet, fmt, fs, str::FromStr};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::{models::custom_api_errors::ApiError, utils::envs};
+
+/// Blocklist used if no `CONTENT_BLOCKLIST_PATH` is configured
+const DEFAULT_BLOCKLIST: &str = include_str!("../../moderation/blocklist.txt");
+
+/// How aggressively content is filtered, set per session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strictness {
+    Off,
+    /// whole words only
+    Low,
+    /// whole words, also written in leetspeak or with repeated letters
+    #[default]
+    Medium,
+    /// also inside other words and across separators like `f.u.c.k`
+    High,
+}
+
+impl fmt::Display for Strictness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Strictness::Off => "off",
+            Strictness::Low => "low",
+            Strictness::Medium => "medium",
+            Strictness::High => "high",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Strictness {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "off" => Ok(Strictness::Off),
+            "low" => Ok(Strictness::Low),
+            "medium" => Ok(Strictness::Medium),
+            "high" => Ok(Strictness::High),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    pub code: String,
+    /// completes "<field> ...", e.g. "contains inappropriate language"
+    pub reason: String,
+}
+
+/// A check content has to pass, filters are combined by the [`Moderator`]
+pub trait ContentFilter: Send + Sync {
+    fn check(&self, text: &str, strictness: Strictness) -> Option<Rejection>;
+}
+
+/// Lowercase and without diacritics
+fn fold(text: &str) -> String {
+    text.nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn unleet(c: char) -> char {
+    match c {
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => c,
+    }
+}
+
+/// `fuuuck` -> `fuck`
+fn collapse_repeats(word: &str) -> String {
+    let mut chars: Vec<char> = word.chars().collect();
+    chars.dedup();
+    chars.into_iter().collect()
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+pub struct BlocklistFilter {
+    words: HashSet<String>,
+    collapsed: HashSet<String>,
+}
+
+impl BlocklistFilter {
+    /// One word per line, empty lines and lines starting with `#` are ignored
+    pub fn parse(list: &str) -> Self {
+        let words: HashSet<String> = list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(fold)
+            .collect();
+
+        let collapsed = words.iter().map(|word| collapse_repeats(word)).collect();
+
+        Self { words, collapsed }
+    }
+
+    fn matches_word(&self, word: &str, strictness: Strictness) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+
+        strictness >= Strictness::Medium && self.collapsed.contains(&collapse_repeats(word))
+    }
+}
+
+impl ContentFilter for BlocklistFilter {
+    fn check(&self, text: &str, strictness: Strictness) -> Option<Rejection> {
+        let folded = fold(text);
+        let unleeted: String = folded.chars().map(unleet).collect();
+
+        let blocked = match strictness {
+            Strictness::Off => false,
+            Strictness::Low => words(&folded).any(|word| self.matches_word(word, strictness)),
+            Strictness::Medium => words(&folded)
+                .chain(words(&unleeted))
+                .any(|word| self.matches_word(word, strictness)),
+            Strictness::High => {
+                let joined: String = unleeted.chars().filter(|c| c.is_alphanumeric()).collect();
+                let joined_collapsed = collapse_repeats(&joined);
+
+                self.words.iter().any(|word| joined.contains(word.as_str()))
+                    || self
+                        .collapsed
+                        .iter()
+                        .any(|word| joined_collapsed.contains(word.as_str()))
+            }
+        };
+
+        blocked.then(|| Rejection {
+            code: "profanity".to_string(),
+            reason: "contains inappropriate language".to_string(),
+        })
+    }
+}
+
+/// Runs content through all filters, the first rejection wins
+pub struct Moderator {
+    filters: Vec<Box<dyn ContentFilter>>,
+}
+
+impl Moderator {
+    pub fn new() -> Self {
+        Self { filters: vec![] }
+    }
+
+    pub fn with_filter(mut self, filter: impl ContentFilter + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// The blocklist of `CONTENT_BLOCKLIST_PATH` or the shipped one
+    fn from_env() -> Self {
+        let blocklist = match envs::content_blocklist_path() {
+            Some(path) => fs::read_to_string(&path).unwrap_or_else(|err| {
+                log::error!(
+                    "Could not read blocklist {}, using the default: {}",
+                    path,
+                    err
+                );
+                DEFAULT_BLOCKLIST.to_string()
+            }),
+            None => DEFAULT_BLOCKLIST.to_string(),
+        };
+
+        Self::new().with_filter(BlocklistFilter::parse(&blocklist))
+    }
+
+    pub fn check(&self, text: &str, strictness: Strictness) -> Option<Rejection> {
+        self.filters
+            .iter()
+            .find_map(|filter| filter.check(text, strictness))
+    }
+}
+
+lazy_static! {
+    static ref MODERATOR: Moderator = Moderator::from_env();
+}
+
+pub fn check_content(text: &str, strictness: Strictness) -> Option<Rejection> {
+    MODERATOR.check(text, strictness)
+}
+
+/// `Validation` error for the field if the content is rejected
+pub fn moderate(text: &str, strictness: Strictness, field: &str) -> Result<(), ApiError> {
+    match check_content(text, strictness) {
+        Some(rejection) => Err(ApiError::Validation {
+            message: format!("{} {}", capitalize(field), rejection.reason),
+            code: rejection.code,
+            field: Some(field.to_string()),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,27 @@
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+use crate::models::custom_api_errors::{ApiError, Result};
+
+/// Hashes a password with argon2, blocking so it should be run with `web::block`
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| ApiError::internal(format!("Could not hash password: {}", e)))?;
+
+    Ok(hash.to_string())
+}
+
+/// Checks a password against an argon2 hash, blocking so it should be run with `web::block`
+pub fn verify_password(password: &str, hash: &str) -> Result<bool> {
+    let hash = PasswordHash::new(hash)
+        .map_err(|e| ApiError::internal(format!("Invalid password hash: {}", e)))?;
+
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_ok())
+}
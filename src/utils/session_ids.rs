@@ -0,0 +1,50 @@
+use rand::{seq::SliceRandom, Rng};
+
+use crate::utils::validators::{valid_alphanumeric_name, valid_content};
+
+/// Session ids are stored as `varchar(20)`
+pub const MAX_SESSION_ID_LENGTH: usize = 20;
+
+// at most 7 characters each, so `adjective-noun-99` stays within the limit
+const ADJECTIVES: &[&str] = &[
+    "amber", "bold", "brave", "breezy", "bright", "bubbly", "calm", "cheeky", "clever", "cosmic",
+    "cozy", "crisp", "curly", "daring", "dizzy", "eager", "fancy", "fluffy", "fuzzy", "gentle",
+    "giddy", "golden", "happy", "jolly", "lively", "lucky", "mellow", "merry", "mighty", "misty",
+    "nimble", "plucky", "proud", "quick", "quiet", "rapid", "rosy", "shiny", "silly", "snappy",
+    "sunny", "swift", "tiny", "witty", "zesty",
+];
+
+const NOUNS: &[&str] = &[
+    "badger", "banjo", "beacon", "bison", "cactus", "comet", "cookie", "coral", "dragon", "falcon",
+    "ferret", "gecko", "giraffe", "harbor", "hippo", "jester", "koala", "lantern", "lemur",
+    "lizard", "llama", "mango", "meadow", "mime", "narwhal", "otter", "panda", "parrot", "pepper",
+    "pickle", "pirate", "puffin", "quokka", "raccoon", "rocket", "tiger", "toucan", "tulip",
+    "turtle", "walrus", "wizard", "yeti",
+];
+
+/// Memorable id like `jolly-otter-42`, it passes the session id validation
+pub fn generate_session_id() -> String {
+    let mut rng = rand::thread_rng();
+
+    let adjective = ADJECTIVES.choose(&mut rng).unwrap_or(&"happy");
+    let noun = NOUNS.choose(&mut rng).unwrap_or(&"otter");
+    let number: u8 = rng.gen_range(1..100);
+
+    format!("{}-{}-{}", adjective, noun, number)
+}
+
+/// Why the id can't be used for a session and the error code, the rules for ids chosen by users
+pub fn invalid_session_id(session_id: &str) -> Option<(String, String)> {
+    if session_id.is_empty() || session_id.chars().count() > MAX_SESSION_ID_LENGTH {
+        return Some((
+            format!(
+                "Session id must be between 1 and {} characters",
+                MAX_SESSION_ID_LENGTH
+            ),
+            "wrong_length".to_string(),
+        ));
+    }
+
+    valid_alphanumeric_name(session_id, "Session id")
+        .or_else(|| valid_content(session_id, "Session id"))
+}
@@ -0,0 +1,23 @@
+use crate::{
+    models::{custom_api_errors::Result, game_result::UserStats},
+    repositories::{cache::Cache, database::Database},
+};
+
+/// Totals of the user across all finished games, cached until one of their games finishes
+pub async fn user_stats(db: &Database, cache: &Cache, user_id: &str) -> Result<UserStats> {
+    match cache.get_user_stats(user_id).await {
+        Ok(Some(stats)) => return Ok(stats),
+        Ok(None) => {}
+        Err(err) => log::error!("Could not get cached stats: {}", err),
+    }
+
+    let results = db.get_game_results_by_user_id(user_id).await?;
+
+    let stats = UserStats::from_results(user_id, &results);
+
+    if let Err(err) = cache.set_user_stats(&stats).await {
+        log::error!("Could not cache stats: {}", err);
+    }
+
+    Ok(stats)
+}
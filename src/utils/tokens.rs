@@ -0,0 +1,58 @@
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::custom_api_errors::{ApiError, Result},
+    utils::envs,
+};
+
+/// How long an access token is valid, clients renew it with their refresh token
+pub const ACCESS_TOKEN_TTL_SECS: i64 = 15 * 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AccessTokenClaims {
+    /// user id
+    sub: String,
+    iat: i64,
+    exp: i64,
+}
+
+pub fn create_access_token(user_id: &str) -> Result<String> {
+    let now = chrono::Utc::now().timestamp();
+
+    let claims = AccessTokenClaims {
+        sub: user_id.to_string(),
+        iat: now,
+        exp: now + ACCESS_TOKEN_TTL_SECS,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(envs::token_secret().as_bytes()),
+    )
+    .map_err(|e| ApiError::internal(format!("Could not create access token: {}", e)))
+}
+
+/// Returns the user id of a valid, unexpired access token
+pub fn verify_access_token(token: &str) -> Result<String> {
+    let token_data = decode::<AccessTokenClaims>(
+        token,
+        &DecodingKey::from_secret(envs::token_secret().as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| ApiError::Unauthorized {
+        message: "Invalid or expired access token".to_string(),
+    })?;
+
+    Ok(token_data.claims.sub)
+}
+
+/// Opaque random token, only meaningful together with its entry in the cache
+pub fn generate_refresh_token() -> String {
+    use rand::Rng;
+
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -1,3 +1,11 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{
+    emoji::is_emoji,
+    moderation::{check_content, Strictness},
+};
+
 pub fn valid_alphanumeric_name(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
     for c in value.chars() {
         if c == '-' || c == '_' {
@@ -34,3 +42,257 @@ pub fn valid_alphanumeric_name(value: &str, validation_obj_name: &str) -> Option
 
     None
 }
+
+const DISPLAY_NAME_MIN_LENGTH: usize = 2;
+const DISPLAY_NAME_MAX_LENGTH: usize = 32;
+/// `users.name` and the nicknames are `varchar(100)`
+const DISPLAY_NAME_MAX_CHARS: usize = 100;
+
+/// NFC normalized, trimmed and with inner whitespace collapsed to single spaces
+pub fn normalize_display_name(value: &str) -> String {
+    value
+        .nfc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Key that words are compared by, words with the same key are duplicates
+pub fn normalize_word_key(value: &str) -> String {
+    normalize_display_name(value).to_lowercase()
+}
+
+/// Unicode names, the length is counted in graphemes so emoji and accents count as one character
+pub fn valid_display_name(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    valid_display_name_format(value, validation_obj_name)
+        .or_else(|| valid_content(&normalize_display_name(value), validation_obj_name))
+}
+
+/// Length and characters of a display name, for names moderated with the content filter of a session
+pub fn valid_display_name_format(
+    value: &str,
+    validation_obj_name: &str,
+) -> Option<(String, String)> {
+    let name = normalize_display_name(value);
+
+    let length = name.graphemes(true).count();
+
+    if !(DISPLAY_NAME_MIN_LENGTH..=DISPLAY_NAME_MAX_LENGTH).contains(&length) {
+        return Some((
+            format!(
+                "{} must be between {} and {} characters",
+                validation_obj_name, DISPLAY_NAME_MIN_LENGTH, DISPLAY_NAME_MAX_LENGTH
+            ),
+            "wrong_length".to_string(),
+        ));
+    }
+
+    if let Some(invalid) = valid_char_count(&name, DISPLAY_NAME_MAX_CHARS, validation_obj_name) {
+        return Some(invalid);
+    }
+
+    if name.chars().any(char::is_control) {
+        return Some((
+            format!(
+                "{} must not contain control characters",
+                validation_obj_name
+            ),
+            "wrong_format".to_string(),
+        ));
+    }
+
+    None
+}
+
+/// Columns limit code points while the validators count graphemes,
+/// and a single grapheme can carry any number of combining marks
+fn valid_char_count(
+    value: &str,
+    max_chars: usize,
+    validation_obj_name: &str,
+) -> Option<(String, String)> {
+    if value.chars().count() > max_chars {
+        return Some((
+            format!("{} is too long", validation_obj_name),
+            "wrong_length".to_string(),
+        ));
+    }
+
+    None
+}
+
+/// Runs the content filter with the default strictness, for content that doesn't belong to a session
+pub fn valid_content(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    check_content(value, Strictness::default()).map(|rejection| {
+        (
+            format!("{} {}", validation_obj_name, rejection.reason),
+            rejection.code,
+        )
+    })
+}
+
+const WORD_MIN_LENGTH: usize = 2;
+const WORD_MAX_LENGTH: usize = 30;
+/// `words.word` and `words.normalized` are `varchar(255)`
+const WORD_MAX_CHARS: usize = 255;
+
+/// Words of any script, the length is counted in graphemes
+pub fn valid_word(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    let word = normalize_display_name(value);
+
+    let length = word.graphemes(true).count();
+
+    if !(WORD_MIN_LENGTH..=WORD_MAX_LENGTH).contains(&length) {
+        return Some((
+            format!(
+                "{} must be between {} and {} characters",
+                validation_obj_name, WORD_MIN_LENGTH, WORD_MAX_LENGTH
+            ),
+            "wrong_length".to_string(),
+        ));
+    }
+
+    // lowercasing never shortens the word, so this also covers `words.normalized`
+    if let Some(invalid) = valid_char_count(
+        &normalize_word_key(&word),
+        WORD_MAX_CHARS,
+        validation_obj_name,
+    ) {
+        return Some(invalid);
+    }
+
+    if word.chars().any(char::is_control) {
+        return Some((
+            format!(
+                "{} must not contain control characters",
+                validation_obj_name
+            ),
+            "wrong_format".to_string(),
+        ));
+    }
+
+    None
+}
+
+const USERNAME_MIN_LENGTH: usize = 3;
+const USERNAME_MAX_LENGTH: usize = 30;
+/// `users.username` is `varchar(120)`
+const USERNAME_MAX_CHARS: usize = 120;
+
+/// Like [`valid_alphanumeric_name`], but letters and digits of any script are allowed
+pub fn valid_username(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    let name = normalize_display_name(value);
+
+    let length = name.graphemes(true).count();
+
+    if !(USERNAME_MIN_LENGTH..=USERNAME_MAX_LENGTH).contains(&length) {
+        return Some((
+            format!(
+                "{} must be between {} and {} characters",
+                validation_obj_name, USERNAME_MIN_LENGTH, USERNAME_MAX_LENGTH
+            ),
+            "wrong_length".to_string(),
+        ));
+    }
+
+    if let Some(invalid) = valid_char_count(&name, USERNAME_MAX_CHARS, validation_obj_name) {
+        return Some(invalid);
+    }
+
+    for c in name.chars() {
+        if c == '-' || c == '_' || is_combining_mark(c) {
+            continue;
+        }
+
+        if c.is_whitespace() {
+            return Some((
+                format!("{} must not contain any whitespace", validation_obj_name),
+                "wrong_format".to_string(),
+            ));
+        }
+
+        if !c.is_alphanumeric() {
+            return Some((
+                format!(
+                    "{} must only contain letters, digits or -/_",
+                    validation_obj_name
+                ),
+                "wrong_format".to_string(),
+            ));
+        }
+
+        // logins are lowercased, so titlecase letters like ǅ would lock the user out
+        if !c.to_lowercase().eq(std::iter::once(c)) {
+            return Some((
+                format!(
+                    "{} must only contain lowercase characters",
+                    validation_obj_name
+                ),
+                "wrong_format".to_string(),
+            ));
+        }
+    }
+
+    None
+}
+
+/// Colors in the `#rrggbb` format
+pub fn valid_hex_color(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    let valid = value.len() == 7
+        && value.starts_with('#')
+        && value.chars().skip(1).all(|c| c.is_ascii_hexdigit());
+
+    if !valid {
+        return Some((
+            format!("{} must be a color like #1a2b3c", validation_obj_name),
+            "wrong_format".to_string(),
+        ));
+    }
+
+    None
+}
+
+/// `users.avatar_emoji` is `varchar(32)`
+const EMOJI_MAX_CHARS: usize = 32;
+
+pub fn valid_emoji(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    let mut graphemes = value.graphemes(true);
+
+    let valid = match (graphemes.next(), graphemes.next()) {
+        (Some(grapheme), None) => is_emoji(grapheme),
+        _ => false,
+    };
+
+    if !valid {
+        return Some((
+            format!("{} must be a single emoji", validation_obj_name),
+            "wrong_format".to_string(),
+        ));
+    }
+
+    valid_char_count(value, EMOJI_MAX_CHARS, validation_obj_name)
+}
+
+/// Locales like `en`, `de-DE` or `ja-JP`
+pub fn valid_locale(value: &str, validation_obj_name: &str) -> Option<(String, String)> {
+    let (language, region) = match value.split_once('-') {
+        Some((language, region)) => (language, Some(region)),
+        None => (value, None),
+    };
+
+    let valid_language =
+        (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    let valid_region = region
+        .map(|region| region.len() == 2 && region.chars().all(|c| c.is_ascii_uppercase()))
+        .unwrap_or(true);
+
+    if !valid_language || !valid_region {
+        return Some((
+            format!("{} must be a locale like en or de-DE", validation_obj_name),
+            "wrong_format".to_string(),
+        ));
+    }
+
+    None
+}
@@ -0,0 +1,48 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::models::custom_api_errors::Result;
+
+/// Wire format negotiated per websocket connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    #[default]
+    Json,
+    #[serde(alias = "messagepack")]
+    Msgpack,
+}
+
+/// Encoded message ready to be written to the websocket
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Encoding {
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Frame> {
+        match self {
+            Encoding::Json => Ok(Frame::Text(serde_json::to_string(value)?)),
+            Encoding::Msgpack => {
+                let mut bytes = Vec::new();
+
+                // Struct fields are written as maps and ids or timestamps as strings,
+                // so the payload has the same shape as the JSON one
+                value.serialize(
+                    &mut rmp_serde::Serializer::new(&mut bytes)
+                        .with_struct_map()
+                        .with_human_readable(),
+                )?;
+
+                Ok(Frame::Binary(bytes))
+            }
+        }
+    }
+
+    pub fn decode_text<T: DeserializeOwned>(text: &str) -> Result<T> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    pub fn decode_binary<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
@@ -1,14 +1,14 @@
+use std::{collections::HashMap, time::Duration};
+
 use actix::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{custom_api_errors::ApiError, user::User};
+use crate::models::{
+    chat_message::ChatMessage, custom_api_errors::ApiError, game_result::GameSummary, user::User,
+};
 
 use super::session::WsCharadeSession;
 
-#[derive(Message)]
-#[rtype(result = "()")]
-pub struct Message(pub String);
-
 #[derive(Message)]
 #[rtype(String)]
 pub struct Connect {
@@ -25,6 +25,32 @@ pub struct Disconnect {
     pub session_id: String,
 }
 
+/// A user changed their profile, sent from the REST api
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct UserUpdated {
+    pub id: String,
+}
+
+/// Members or the waitlist of a session changed, sent from the REST api
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct MembersChanged {
+    pub session_id: String,
+    /// removed by the admin
+    pub kicked: Option<String>,
+    /// moved from the waitlist into the session
+    pub promoted: Vec<String>,
+}
+
+/// A rematch of the session was created, sent from the REST api
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct RematchCreated {
+    pub session_id: String,
+    pub new_session_id: String,
+}
+
 #[derive(Message)]
 #[rtype(result = "()")]
 pub struct ClientMessageWrapper {
@@ -38,6 +64,24 @@ pub struct ClientMessageWrapper {
 pub enum ClientMessage {
     StartSession { session_id: String },
     AddWord { word: String },
+    Guess { text: String },
+    Skip,
+    Chat { message: String },
+    React { reaction: String },
+}
+
+impl ClientMessage {
+    /// name of the message type, used as key for rate limits
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            ClientMessage::StartSession { .. } => "StartSession",
+            ClientMessage::AddWord { .. } => "AddWord",
+            ClientMessage::Guess { .. } => "Guess",
+            ClientMessage::Skip => "Skip",
+            ClientMessage::Chat { .. } => "Chat",
+            ClientMessage::React { .. } => "React",
+        }
+    }
 }
 
 #[derive(Message, Debug, Serialize, Clone)]
@@ -55,13 +99,101 @@ pub enum ServerMessage {
         number_of_words: u16,
         my_words: Vec<String>,
     },
+    GameStarted {
+        performer_id: String,
+        number_of_words: u16,
+    },
+    /// Only sent to the performer
+    SecretWord {
+        word: String,
+    },
+    Guess {
+        user_id: String,
+        text: String,
+    },
+    /// Only sent to the guesser
+    CloseGuess {
+        text: String,
+    },
+    WordGuessed {
+        word: String,
+        guessed_by: String,
+        performer_id: String,
+        remaining_words: u16,
+        scores: HashMap<String, u32>,
+    },
+    /// The word goes back to the end of the remaining words, it is not revealed
+    WordSkipped {
+        performer_id: String,
+        remaining_words: u16,
+    },
+    GameFinished {
+        scores: HashMap<String, u32>,
+    },
+    /// Follows `GameFinished` once the results are stored
+    GameSummary {
+        summary: Box<GameSummary>,
+    },
+    Chat {
+        message: ChatMessage,
+    },
+    /// Reactions of a short window, counted per reaction
+    Reactions {
+        reactions: HashMap<String, u32>,
+    },
+    /// Only sent to waitlisted users, whenever their position changes
+    WaitlistPosition {
+        position: u32,
+    },
+    /// Only sent to the promoted user, they are a member now
+    Promoted {
+        session_id: String,
+    },
+    /// Only sent to the kicked user
+    Kicked {
+        session_id: String,
+    },
+    /// The group moves on to a new session with the same members and settings
+    Rematch {
+        new_session_id: String,
+    },
     Error {
         error: String,
+        /// kind of the error, e.g. `duplicate` or `internal`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        code: Option<String>,
+    },
+    RateLimited {
+        message_type: String,
+        retry_after_ms: u64,
     },
 }
 
+impl ServerMessage {
+    pub fn rate_limited(message_type: &str, retry_after: Duration) -> ServerMessage {
+        ServerMessage::RateLimited {
+            message_type: message_type.to_string(),
+            retry_after_ms: retry_after.as_millis() as u64,
+        }
+    }
+}
+
 impl From<ApiError> for ServerMessage {
     fn from(error: ApiError) -> ServerMessage {
+        let code = match &error {
+            ApiError::Internal { .. } => "internal",
+            ApiError::BadRequest { .. } => "bad_request",
+            ApiError::NotFound { .. } => "not_found",
+            ApiError::Unauthorized { .. } => "unauthorized",
+            ApiError::UniqueViolation { .. } => "duplicate",
+            ApiError::Validation { code, .. } => code,
+            ApiError::CheckViolation { .. }
+            | ApiError::ForeignKeyViolation { .. }
+            | ApiError::NotNullViolation { .. } => "invalid",
+            ApiError::TooManyRequests { .. } => "too_many_requests",
+        }
+        .to_string();
+
         match error {
             ApiError::Internal { message }
             | ApiError::BadRequest { message }
@@ -71,7 +203,11 @@ impl From<ApiError> for ServerMessage {
             | ApiError::Validation { message, .. }
             | ApiError::CheckViolation { message }
             | ApiError::ForeignKeyViolation { message }
-            | ApiError::NotNullViolation { message } => ServerMessage::Error { error: message },
+            | ApiError::NotNullViolation { message }
+            | ApiError::TooManyRequests { message, .. } => ServerMessage::Error {
+                error: message,
+                code: Some(code),
+            },
         }
     }
 }
@@ -1,5 +1,7 @@
+pub mod encoding;
 mod messages;
+pub mod rate_limiter;
 pub mod server;
 pub mod session;
 
-pub use messages::{ClientMessage, ClientMessageWrapper};
+pub use messages::{ClientMessage, MembersChanged, RematchCreated, UserUpdated};
@@ -0,0 +1,212 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+
+use crate::utils::envs;
+
+use super::ClientMessage;
+
+/// Allows `burst` messages at once, refilled completely over `period`
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub burst: u32,
+    pub period: Duration,
+}
+
+impl RateLimit {
+    fn refill_per_sec(&self) -> f64 {
+        self.burst as f64 / self.period.as_secs_f64()
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+
+        self.tokens =
+            (self.tokens + elapsed * self.limit.refill_per_sec()).min(self.limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    /// takes a token or returns how long to wait until one is available
+    fn try_take(&mut self) -> Result<(), Duration> {
+        self.refill();
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let missing = 1.0 - self.tokens;
+
+        Err(Duration::from_secs_f64(
+            missing / self.limit.refill_per_sec(),
+        ))
+    }
+
+    fn is_full(&mut self) -> bool {
+        self.refill();
+
+        self.tokens >= self.limit.burst as f64
+    }
+}
+
+/// Limits per client message type
+#[derive(Debug, Clone)]
+pub struct RateLimits {
+    default: RateLimit,
+    per_message: HashMap<String, RateLimit>,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        let per_message = HashMap::from([
+            (
+                "AddWord".to_string(),
+                RateLimit {
+                    burst: 5,
+                    period: Duration::from_secs(10),
+                },
+            ),
+            (
+                "Chat".to_string(),
+                RateLimit {
+                    burst: 10,
+                    period: Duration::from_secs(10),
+                },
+            ),
+            (
+                "React".to_string(),
+                RateLimit {
+                    burst: 5,
+                    period: Duration::from_secs(2),
+                },
+            ),
+            (
+                "StartSession".to_string(),
+                RateLimit {
+                    burst: 2,
+                    period: Duration::from_secs(10),
+                },
+            ),
+        ]);
+
+        Self {
+            default: RateLimit {
+                burst: 20,
+                period: Duration::from_secs(10),
+            },
+            per_message,
+        }
+    }
+}
+
+lazy_static! {
+    static ref RATE_LIMITS: RateLimits = RateLimits::from_env();
+}
+
+impl RateLimits {
+    /// Limits from `WS_RATE_LIMITS`, parsed once so invalid entries are only reported at startup
+    pub fn configured() -> Self {
+        RATE_LIMITS.clone()
+    }
+
+    /// Default limits, overridden by `WS_RATE_LIMITS`.
+    ///
+    /// The variable is a comma separated list of `<MessageType>=<burst>/<seconds>`,
+    /// e.g. `AddWord=5/10,default=20/10`.
+    fn from_env() -> Self {
+        let mut limits = Self::default();
+
+        let Some(config) = envs::ws_rate_limits() else {
+            return limits;
+        };
+
+        for entry in config.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            match Self::parse_entry(entry) {
+                Some((message_type, limit)) if message_type == "default" => limits.default = limit,
+                Some((message_type, limit)) => {
+                    limits.per_message.insert(message_type, limit);
+                }
+                None => log::warn!("Ignoring invalid websocket rate limit: {}", entry),
+            }
+        }
+
+        limits
+    }
+
+    fn parse_entry(entry: &str) -> Option<(String, RateLimit)> {
+        let (message_type, limit) = entry.split_once('=')?;
+        let (burst, seconds) = limit.split_once('/')?;
+
+        let burst = burst.trim().parse::<u32>().ok().filter(|b| *b > 0)?;
+        let seconds = seconds.trim().parse::<f64>().ok().filter(|s| *s > 0.0)?;
+
+        Some((
+            message_type.trim().to_string(),
+            RateLimit {
+                burst,
+                period: Duration::from_secs_f64(seconds),
+            },
+        ))
+    }
+
+    fn for_message(&self, message_type: &str) -> RateLimit {
+        self.per_message
+            .get(message_type)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Token buckets for one client, one per message type
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: RateLimits,
+    buckets: HashMap<&'static str, TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Returns the time to wait before retrying if the message is throttled
+    pub fn check(&mut self, msg: &ClientMessage) -> Result<(), Duration> {
+        let message_type = msg.message_type();
+
+        let limits = &self.limits;
+
+        self.buckets
+            .entry(message_type)
+            .or_insert_with(|| TokenBucket::new(limits.for_message(message_type)))
+            .try_take()
+    }
+
+    /// true if no bucket has been drained, so dropping the limiter loses no state
+    pub fn is_idle(&mut self) -> bool {
+        self.buckets.values_mut().all(TokenBucket::is_full)
+    }
+}
@@ -1,23 +1,42 @@
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use crate::{
     models::{user::User, word::NewWord},
     repositories::{cache::Cache, database::Database},
-    websocket::{messages::ServerMessage, session::WsCharadeSession},
+    websocket::{
+        messages::ServerMessage,
+        rate_limiter::{RateLimiter, RateLimits},
+        session::WsCharadeSession,
+    },
 };
-use actix::{Actor, Addr, Context};
+use actix::{Actor, Addr, AsyncContext, Context};
 
-use self::utils::{Result, ServerError, ServerResult};
+use self::{
+    reactions::PendingReactions,
+    utils::{Result, ServerError, ServerResult},
+};
 
+mod chat;
+mod game;
 mod handlers;
+mod reactions;
 mod utils;
+mod waitlist;
+
+/// How often limiters of users who left while throttled are dropped
+const RATE_LIMITER_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone)]
 pub struct CharadeServer {
     sessions: Arc<Mutex<HashMap<String, Addr<WsCharadeSession>>>>,
+    rate_limiters: Arc<Mutex<HashMap<String, RateLimiter>>>,
+    rate_limits: RateLimits,
+    /// reactions waiting to be broadcast, never persisted
+    reactions: Arc<Mutex<PendingReactions>>,
     db: Database,
     cache: Cache,
 }
@@ -26,6 +45,9 @@ impl CharadeServer {
     pub fn new(db: Database, cache: Cache) -> CharadeServer {
         CharadeServer {
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            rate_limiters: Arc::new(Mutex::new(HashMap::new())),
+            rate_limits: RateLimits::configured(),
+            reactions: Arc::new(Mutex::new(HashMap::new())),
             db,
             cache,
         }
@@ -34,24 +56,14 @@ impl CharadeServer {
     pub async fn add_word_to_session(&self, session_id: &str, word: &str, user_id: &str) -> Result {
         let db = self.db.clone();
 
-        db.add_word_to_session(NewWord {
-            session_id: session_id.to_string(),
-            word: word.to_string(),
-            user_id: user_id.to_string(),
-        })
-        .await
-        .map_err(|_| ServerError::Private {
-            id: user_id.to_string(),
-            error: format!("Word '{word}' already in session"),
-        })?;
-
-        let words =
-            db.get_words_by_session_id(session_id)
-                .await
-                .map_err(|_| ServerError::Private {
-                    id: user_id.to_string(),
-                    error: format!("Word '{word}' already in session"),
-                })?;
+        db.add_word_to_session(NewWord::new(word, session_id, user_id))
+            .await
+            .map_err(|err| ServerError::api(user_id, err))?;
+
+        let words = db
+            .get_words_by_session_id(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
 
         Ok(ServerResult::Multiple(vec![
             ServerResult::Broadcast {
@@ -104,4 +116,10 @@ impl CharadeServer {
 
 impl Actor for CharadeServer {
     type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(RATE_LIMITER_SWEEP_INTERVAL, |act, _| {
+            act.sweep_rate_limiters();
+        });
+    }
 }
@@ -0,0 +1,65 @@
+use crate::{
+    models::chat_message::{NewChatMessage, MAX_CHAT_MESSAGE_LENGTH},
+    utils::{guesses::redact_secret, moderation::moderate},
+    websocket::{messages::ServerMessage, server::CharadeServer},
+};
+
+use super::utils::{Result, ServerError, ServerResult};
+
+impl CharadeServer {
+    /// Stores and broadcasts a chat message, the current secret word is masked while it is played
+    pub async fn chat(&self, session_id: &str, message: &str, user_id: &str) -> Result {
+        let message = message.trim();
+
+        if message.is_empty() {
+            return Err(ServerError::private(
+                user_id,
+                "Chat messages can't be empty",
+            ));
+        }
+
+        if message.chars().count() > MAX_CHAT_MESSAGE_LENGTH {
+            return Err(ServerError::private(
+                user_id,
+                &format!("Chat messages can have at most {MAX_CHAT_MESSAGE_LENGTH} characters"),
+            ));
+        }
+
+        let session = self
+            .db
+            .get_session_by_id(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        moderate(message, session.content_filter(), "message")
+            .map_err(|err| ServerError::api(user_id, err))?;
+
+        // without the game the word can't be masked, so nothing is sent rather than leaking it
+        let game = self
+            .cache
+            .get_game(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        let message = match game.and_then(|game| game.current_word) {
+            Some(word) => redact_secret(message, &word),
+            None => message.to_string(),
+        };
+
+        let message = self
+            .db
+            .add_chat_message(NewChatMessage {
+                session_id: session_id.to_string(),
+                user_id: user_id.to_string(),
+                message,
+            })
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        Ok(ServerResult::Broadcast {
+            session_id: session_id.to_string(),
+            msg: ServerMessage::Chat { message },
+            exclude: None,
+        })
+    }
+}
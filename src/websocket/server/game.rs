@@ -0,0 +1,341 @@
+use std::collections::HashSet;
+
+use chrono::{NaiveDateTime, Utc};
+use rand::seq::SliceRandom;
+
+use crate::{
+    models::{
+        custom_api_errors::ApiError,
+        game::GameState,
+        game_result::{GameSummary, NewGame},
+        session::SessionPhase,
+    },
+    utils::guesses::{check_guess, normalize_guess, GuessResult},
+    websocket::{messages::ServerMessage, server::CharadeServer},
+};
+
+use super::utils::{Result, ServerError, ServerResult};
+
+/// Player words are topped up with words of the selected packs up to this number
+const MIN_WORDS_PER_GAME: usize = 20;
+
+impl CharadeServer {
+    /// Starts a new game with all words of the session, the admin performs the first word.
+    /// If the players did not submit enough words, words of the selected word packs are mixed in.
+    pub async fn start_game(&self, session_id: &str, user_id: &str) -> Result {
+        let session = self
+            .db
+            .get_session_by_id(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        if session.admin_user_id != user_id {
+            return Err(ServerError::private(
+                user_id,
+                "Only the admin can start the session",
+            ));
+        }
+
+        let mut words: Vec<String> = self
+            .db
+            .get_words_by_session_id(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?
+            .into_iter()
+            .map(|w| w.word)
+            .collect();
+
+        if words.len() < MIN_WORDS_PER_GAME {
+            let missing = MIN_WORDS_PER_GAME - words.len();
+
+            // a few more than needed, as some of them might already be in the pool
+            let pack_words = self
+                .db
+                .get_random_pack_words(session_id, (missing * 2) as i64)
+                .await
+                .map_err(|err| ServerError::internal(user_id, err))?;
+
+            let mut known: HashSet<String> = words.iter().map(|w| normalize_guess(w)).collect();
+
+            let top_up: Vec<String> = pack_words
+                .into_iter()
+                .filter(|word| known.insert(normalize_guess(word)))
+                .take(missing)
+                .collect();
+
+            words.extend(top_up);
+        }
+
+        if words.is_empty() {
+            return Err(ServerError::private(
+                user_id,
+                "Add some words or select a word pack first",
+            ));
+        }
+
+        words.shuffle(&mut rand::thread_rng());
+
+        let number_of_words = words.len() as u16;
+        let game = GameState::new(user_id, words);
+
+        self.cache
+            .set_game(session_id, &game)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        self.db
+            .set_session_phase(session_id, SessionPhase::Playing)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        Ok(ServerResult::Multiple(vec![
+            ServerResult::Broadcast {
+                session_id: session_id.to_string(),
+                msg: ServerMessage::GameStarted {
+                    performer_id: game.performer_id.clone(),
+                    number_of_words,
+                },
+                exclude: None,
+            },
+            ServerResult::Private {
+                id: game.performer_id,
+                msg: ServerMessage::SecretWord {
+                    word: game.current_word.unwrap_or_default(),
+                },
+            },
+        ]))
+    }
+
+    /// Checks the guess against the current word, a correct guess moves on to the next word
+    pub async fn guess(&self, session_id: &str, text: &str, user_id: &str) -> Result {
+        let game = self
+            .cache
+            .get_game(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?
+            .ok_or_else(|| ServerError::private(user_id, "The game has not started yet"))?;
+
+        if game.performer_id == user_id {
+            return Err(ServerError::private(
+                user_id,
+                "You can't guess your own word",
+            ));
+        }
+
+        let Some(word) = game.current_word.clone() else {
+            return Err(ServerError::private(user_id, "The game is already over"));
+        };
+
+        // only used for the statistics, so a failure doesn't stop the guess
+        if let Err(err) = self.cache.count_guess(&game, user_id).await {
+            log::error!("Could not count guess: {}", err);
+        }
+
+        match check_guess(text, &word) {
+            GuessResult::Wrong => Ok(ServerResult::Broadcast {
+                session_id: session_id.to_string(),
+                msg: ServerMessage::Guess {
+                    user_id: user_id.to_string(),
+                    text: text.to_string(),
+                },
+                exclude: None,
+            }),
+            GuessResult::Close => Ok(ServerResult::Private {
+                id: user_id.to_string(),
+                msg: ServerMessage::CloseGuess {
+                    text: text.to_string(),
+                },
+            }),
+            GuessResult::Correct => self.word_guessed(session_id, game, user_id).await,
+        }
+    }
+
+    /// The performer skips the current word, it comes up again after the remaining words
+    pub async fn skip_word(&self, session_id: &str, user_id: &str) -> Result {
+        let mut game = self
+            .cache
+            .get_game(session_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?
+            .ok_or_else(|| ServerError::private(user_id, "The game has not started yet"))?;
+
+        if game.performer_id != user_id {
+            return Err(ServerError::private(
+                user_id,
+                "Only the performer can skip the word",
+            ));
+        }
+
+        if game.is_finished() {
+            return Err(ServerError::private(user_id, "The game is already over"));
+        }
+
+        // a correct guess of the same word might be faster
+        let claimed = self
+            .cache
+            .claim_game_round(&game, user_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        if !claimed {
+            return Ok(ServerResult::None);
+        }
+
+        game.word_skipped();
+
+        self.cache
+            .set_game(session_id, &game)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        Ok(ServerResult::Multiple(vec![
+            ServerResult::Broadcast {
+                session_id: session_id.to_string(),
+                msg: ServerMessage::WordSkipped {
+                    performer_id: game.performer_id.clone(),
+                    remaining_words: game.remaining_words.len() as u16 + 1,
+                },
+                exclude: None,
+            },
+            ServerResult::Private {
+                id: game.performer_id,
+                msg: ServerMessage::SecretWord {
+                    word: game.current_word.unwrap_or_default(),
+                },
+            },
+        ]))
+    }
+
+    async fn word_guessed(&self, session_id: &str, mut game: GameState, user_id: &str) -> Result {
+        let claimed = self
+            .cache
+            .claim_game_round(&game, user_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        // someone else was faster
+        if !claimed {
+            return Ok(ServerResult::None);
+        }
+
+        let performer_id = game.performer_id.clone();
+
+        game.word_guessed(user_id);
+
+        self.cache
+            .set_game(session_id, &game)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        let session_phase = if game.is_finished() {
+            SessionPhase::Finished
+        } else {
+            SessionPhase::Playing
+        };
+
+        // also counts as activity while the game is running
+        self.db
+            .set_session_phase(session_id, session_phase)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        let summary = match game.is_finished() {
+            true => match self.save_game_results(session_id, &game).await {
+                Ok(summary) => Some(summary),
+                Err(err) => {
+                    log::error!("Could not save results of game {}: {}", game.id, err);
+                    None
+                }
+            },
+            false => None,
+        };
+
+        let guessed_word = game
+            .guessed_words
+            .last()
+            .map(|w| w.word.clone())
+            .unwrap_or_default();
+
+        let mut results = vec![ServerResult::Broadcast {
+            session_id: session_id.to_string(),
+            msg: ServerMessage::WordGuessed {
+                word: guessed_word,
+                guessed_by: user_id.to_string(),
+                performer_id,
+                remaining_words: game.remaining_words.len() as u16 + u16::from(!game.is_finished()),
+                scores: game.scores.clone(),
+            },
+            exclude: None,
+        }];
+
+        match game.current_word {
+            Some(word) => results.push(ServerResult::Private {
+                id: game.performer_id,
+                msg: ServerMessage::SecretWord { word },
+            }),
+            None => results.push(ServerResult::Broadcast {
+                session_id: session_id.to_string(),
+                msg: ServerMessage::GameFinished {
+                    scores: game.scores,
+                },
+                exclude: None,
+            }),
+        }
+
+        if let Some(summary) = summary {
+            results.push(ServerResult::Broadcast {
+                session_id: session_id.to_string(),
+                msg: ServerMessage::GameSummary {
+                    summary: Box::new(summary),
+                },
+                exclude: None,
+            });
+        }
+
+        Ok(ServerResult::Multiple(results))
+    }
+
+    /// Stores the results of the finished game, the stats of its players and the summary are computed from them
+    async fn save_game_results(
+        &self,
+        session_id: &str,
+        game: &GameState,
+    ) -> Result<GameSummary, ApiError> {
+        let member_ids: Vec<String> = self
+            .db
+            .get_users_by_session_id(session_id)
+            .await?
+            .into_iter()
+            .map(|user| user.id)
+            .collect();
+
+        let guesses = self.cache.get_guess_counts(game).await?;
+
+        let players = game.player_results(&member_ids, &guesses);
+        let player_ids: Vec<String> = players.iter().map(|p| p.user_id.clone()).collect();
+
+        let summary = self
+            .db
+            .save_game(
+                NewGame {
+                    id: game.id,
+                    session_id: session_id.to_string(),
+                    started_at: NaiveDateTime::from_timestamp_millis(game.started_at)
+                        .unwrap_or_default(),
+                    finished_at: Utc::now().naive_utc(),
+                },
+                players,
+                game.game_words(),
+            )
+            .await?;
+
+        // the stats expire on their own, so a failure doesn't drop the summary
+        for player_id in player_ids {
+            if let Err(err) = self.cache.invalidate_user_stats(&player_id).await {
+                log::error!("Could not invalidate stats of user {}: {}", player_id, err);
+            }
+        }
+
+        Ok(summary)
+    }
+}
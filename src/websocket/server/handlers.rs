@@ -3,7 +3,10 @@ use actix::{Context, Handler, ResponseFuture};
 use crate::{
     repositories::cache::Cache,
     websocket::{
-        messages::{ClientMessageWrapper, Connect, Disconnect},
+        messages::{
+            ClientMessageWrapper, Connect, Disconnect, MembersChanged, RematchCreated,
+            ServerMessage, UserUpdated,
+        },
         server::{
             utils::{ServerMessageHandler, ServerResult},
             CharadeServer,
@@ -23,27 +26,13 @@ impl Handler<Connect> for CharadeServer {
 
         session_lock.insert(msg.id.clone(), msg.addr);
 
-        let cache_key = Cache::session_users_key(&msg.session_id);
-
         let this = self.clone();
         let user_id = msg.id.clone();
 
         Box::pin(async move {
-            let _ = this
-                .cache
-                .push_strings_to_set(&cache_key, vec![&user_id])
-                .await;
-
-            let res = this.handle_update_users(&msg.session_id).await;
-
-            if let Ok(res) = res {
-                ServerResult::Broadcast {
-                    session_id: msg.session_id.to_string(),
-                    msg: res,
-                    exclude: None,
-                }
-                .distribute_message(&this)
-                .await;
+            match this.connect_to_session(&msg.session_id, &user_id).await {
+                Ok(res) => res.distribute_message(&this).await,
+                Err(res) => res.distribute_message(&this).await,
             }
 
             id
@@ -63,6 +52,8 @@ impl Handler<Disconnect> for CharadeServer {
 
         sessions_lock.remove(&user_id);
 
+        self.release_rate_limiter(&user_id);
+
         let this = self.clone();
 
         Box::pin(async move {
@@ -70,6 +61,13 @@ impl Handler<Disconnect> for CharadeServer {
                 .cache
                 .remove_string_from_set(&cache_key, &user_id)
                 .await;
+            let _ = this
+                .cache
+                .remove_string_from_set(
+                    &Cache::session_waiting_users_key(&msg.session_id),
+                    &user_id,
+                )
+                .await;
 
             let res = this.handle_update_users(&msg.session_id).await;
 
@@ -93,6 +91,11 @@ impl Handler<ClientMessageWrapper> for CharadeServer {
         let this = self.clone();
 
         Box::pin(async move {
+            if let Err(err) = this.check_rate_limit(&msg.id, &msg.message) {
+                err.distribute_message(&this).await;
+                return;
+            }
+
             let res = this
                 .handle_incoming_client_message(msg.message, &msg.id, &msg.session_id)
                 .await;
@@ -104,3 +107,115 @@ impl Handler<ClientMessageWrapper> for CharadeServer {
         })
     }
 }
+
+impl Handler<UserUpdated> for CharadeServer {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: UserUpdated, _: &mut Context<Self>) -> Self::Result {
+        let this = self.clone();
+
+        Box::pin(async move {
+            let sessions = match this.db.get_sessions_by_user(&msg.id).await {
+                Ok(sessions) => sessions,
+                Err(err) => {
+                    log::error!("Could not get sessions of updated user: {}", err);
+                    return;
+                }
+            };
+
+            for session in sessions {
+                if let Ok(res) = this.handle_update_users(&session.id).await {
+                    ServerResult::Broadcast {
+                        session_id: session.id,
+                        msg: res,
+                        exclude: None,
+                    }
+                    .distribute_message(&this)
+                    .await;
+                }
+            }
+        })
+    }
+}
+
+impl Handler<MembersChanged> for CharadeServer {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: MembersChanged, _: &mut Context<Self>) -> Self::Result {
+        let this = self.clone();
+
+        Box::pin(async move {
+            if let Some(kicked) = &msg.kicked {
+                ServerResult::Private {
+                    id: kicked.clone(),
+                    msg: ServerMessage::Kicked {
+                        session_id: msg.session_id.clone(),
+                    },
+                }
+                .distribute_message(&this)
+                .await;
+
+                // no more broadcasts of this session
+                let _ = this
+                    .cache
+                    .remove_string_from_set(&Cache::session_users_key(&msg.session_id), kicked)
+                    .await;
+            }
+
+            for id in msg.promoted {
+                // a connected user gets the broadcasts of the session from now on
+                let _ = this
+                    .cache
+                    .move_string_between_sets(
+                        &Cache::session_waiting_users_key(&msg.session_id),
+                        &Cache::session_users_key(&msg.session_id),
+                        &id,
+                    )
+                    .await;
+
+                ServerResult::Private {
+                    id,
+                    msg: ServerMessage::Promoted {
+                        session_id: msg.session_id.clone(),
+                    },
+                }
+                .distribute_message(&this)
+                .await;
+            }
+
+            if let Ok(res) = this.waitlist_positions(&msg.session_id).await {
+                res.distribute_message(&this).await;
+            }
+
+            if let Ok(res) = this.handle_update_users(&msg.session_id).await {
+                ServerResult::Broadcast {
+                    session_id: msg.session_id,
+                    msg: res,
+                    exclude: None,
+                }
+                .distribute_message(&this)
+                .await;
+            }
+        })
+    }
+}
+
+impl Handler<RematchCreated> for CharadeServer {
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, msg: RematchCreated, _: &mut Context<Self>) -> Self::Result {
+        let this = self.clone();
+
+        Box::pin(async move {
+            ServerResult::Broadcast {
+                session_id: msg.session_id,
+                msg: ServerMessage::Rematch {
+                    new_session_id: msg.new_session_id,
+                },
+                exclude: None,
+            }
+            .distribute_message(&this)
+            .await;
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::{
+    utils::validators::valid_emoji,
+    websocket::{messages::ServerMessage, server::CharadeServer},
+};
+
+use super::utils::{Result, ServerError, ServerMessageHandler, ServerResult};
+
+/// Reactions of a session are collected for this long and then sent as one message
+const REACTION_WINDOW: Duration = Duration::from_millis(500);
+
+/// Text signals that can be sent besides single emojis
+const SIGNALS: &[&str] = &["hint!", "louder!", "again!", "close!"];
+
+/// Reaction counts per session of the current window
+pub type PendingReactions = HashMap<String, HashMap<String, u32>>;
+
+impl CharadeServer {
+    /// Counts the reaction, the first reaction of a window schedules the broadcast of all of them
+    pub async fn react(&self, session_id: &str, reaction: &str, user_id: &str) -> Result {
+        let reaction = reaction.trim();
+
+        if !SIGNALS.contains(&reaction) && valid_emoji(reaction, "Reaction").is_some() {
+            return Err(ServerError::private(
+                user_id,
+                "Reactions must be a single emoji or a signal like 'hint!'",
+            ));
+        }
+
+        let window_started = {
+            let mut reactions_lock = self.reactions.lock().unwrap();
+
+            let window_started = !reactions_lock.contains_key(session_id);
+
+            *reactions_lock
+                .entry(session_id.to_string())
+                .or_default()
+                .entry(reaction.to_string())
+                .or_default() += 1;
+
+            window_started
+        };
+
+        if window_started {
+            let this = self.clone();
+            let session_id = session_id.to_string();
+
+            actix::spawn(async move {
+                actix::clock::sleep(REACTION_WINDOW).await;
+
+                this.flush_reactions(&session_id).await;
+            });
+        }
+
+        Ok(ServerResult::None)
+    }
+
+    async fn flush_reactions(&self, session_id: &str) {
+        let reactions = self.reactions.lock().unwrap().remove(session_id);
+
+        let Some(reactions) = reactions else {
+            return;
+        };
+
+        ServerResult::Broadcast {
+            session_id: session_id.to_string(),
+            msg: ServerMessage::Reactions { reactions },
+            exclude: None,
+        }
+        .distribute_message(self)
+        .await;
+    }
+}
@@ -1,8 +1,11 @@
+use std::time::Duration;
+
 use crate::{
     models::custom_api_errors::ApiError,
     repositories::cache::Cache,
     websocket::{
-        messages::ServerMessage, server::CharadeServer, session::WsCharadeSession, ClientMessage,
+        messages::ServerMessage, rate_limiter::RateLimiter, server::CharadeServer,
+        session::WsCharadeSession, ClientMessage,
     },
 };
 use actix::Addr;
@@ -66,9 +69,48 @@ pub enum ServerError {
         session_id: String,
         error: String,
     },
+    RateLimited {
+        id: String,
+        message_type: String,
+        retry_after: Duration,
+    },
+    /// Sent with the message and code of the api error
+    Api {
+        id: String,
+        error: ApiError,
+    },
     None,
 }
 
+impl ServerError {
+    pub fn private(id: &str, error: &str) -> ServerError {
+        ServerError::Private {
+            id: id.to_string(),
+            error: error.to_string(),
+        }
+    }
+
+    pub fn api(id: &str, error: ApiError) -> ServerError {
+        match error {
+            ApiError::Internal { .. } => ServerError::internal(id, error),
+            _ => ServerError::Api {
+                id: id.to_string(),
+                error,
+            },
+        }
+    }
+
+    /// Logs the error and sends a generic message, so internals don't leak to clients
+    pub fn internal(id: &str, err: ApiError) -> ServerError {
+        log::error!("Could not handle client message: {}", err);
+
+        ServerError::Api {
+            id: id.to_string(),
+            error: ApiError::internal("Something went wrong, please try again".to_string()),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl ServerMessageHandler for ServerError {
     async fn distribute_message(&self, server: &CharadeServer) {
@@ -77,6 +119,7 @@ impl ServerMessageHandler for ServerError {
                 id,
                 ServerMessage::Error {
                     error: error.to_string(),
+                    code: None,
                 },
             ),
             ServerError::Broadcast { session_id, error } => {
@@ -85,6 +128,7 @@ impl ServerMessageHandler for ServerError {
                         session_id,
                         ServerMessage::Error {
                             error: error.to_string(),
+                            code: None,
                         },
                         None,
                     )
@@ -94,6 +138,12 @@ impl ServerMessageHandler for ServerError {
                     log::error!("Could not broadcast message: {}", err);
                 }
             }
+            ServerError::RateLimited {
+                id,
+                message_type,
+                retry_after,
+            } => server.send(id, ServerMessage::rate_limited(message_type, *retry_after)),
+            ServerError::Api { id, error } => server.send(id, ServerMessage::from(error.clone())),
             ServerError::None => {
                 log::error!("Could not distribute message: {:?}", self);
             }
@@ -151,18 +201,71 @@ impl CharadeServer {
         client_id: &str,
         session_id: &str,
     ) -> Result {
+        self.check_membership(session_id, client_id).await?;
+
         match msg {
-            ClientMessage::StartSession { session_id } => {
-                println!("Starting session: {session_id}");
-                Ok(ServerResult::None)
+            ClientMessage::StartSession {
+                session_id: requested_session_id,
+            } => {
+                println!("Starting session: {requested_session_id}");
+
+                // a connection can only start the session it is connected to
+                if requested_session_id != session_id {
+                    return Err(ServerError::Private {
+                        id: client_id.to_string(),
+                        error: "You are not connected to this session".to_string(),
+                    });
+                }
+
+                self.start_game(session_id, client_id).await
             }
             ClientMessage::AddWord { word } => {
                 println!("Adding word: {word} to session: {session_id} for user: {client_id}");
                 self.add_word_to_session(session_id, &word, client_id).await
             }
+            ClientMessage::Guess { text } => self.guess(session_id, &text, client_id).await,
+            ClientMessage::Skip => self.skip_word(session_id, client_id).await,
+            ClientMessage::Chat { message } => self.chat(session_id, &message, client_id).await,
+            ClientMessage::React { reaction } => self.react(session_id, &reaction, client_id).await,
         }
     }
 
+    /// Applies the per user limits, which also hold across several connections and reconnects
+    pub fn check_rate_limit(&self, user_id: &str, msg: &ClientMessage) -> Result<()> {
+        let mut limiters_lock = self.rate_limiters.lock().unwrap();
+
+        limiters_lock
+            .entry(user_id.to_string())
+            .or_insert_with(|| RateLimiter::new(self.rate_limits.clone()))
+            .check(msg)
+            .map_err(|retry_after| ServerError::RateLimited {
+                id: user_id.to_string(),
+                message_type: msg.message_type().to_string(),
+                retry_after,
+            })
+    }
+
+    /// Drops the limiter of a disconnected user once it has no throttling state left
+    pub fn release_rate_limiter(&self, user_id: &str) {
+        let mut limiters_lock = self.rate_limiters.lock().unwrap();
+
+        if let Some(limiter) = limiters_lock.get_mut(user_id) {
+            if limiter.is_idle() {
+                limiters_lock.remove(user_id);
+            }
+        }
+    }
+
+    /// Drops the limiters of disconnected users that have become idle since they left,
+    /// `release_rate_limiter` keeps the ones still throttled at disconnect
+    pub fn sweep_rate_limiters(&self) {
+        let sessions_lock = self.sessions.lock().unwrap();
+        let mut limiters_lock = self.rate_limiters.lock().unwrap();
+
+        limiters_lock
+            .retain(|user_id, limiter| sessions_lock.contains_key(user_id) || !limiter.is_idle());
+    }
+
     async fn get_cached_session_users(&self, session_id: &str) -> Vec<String> {
         let res = self
             .cache
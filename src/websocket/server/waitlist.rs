@@ -0,0 +1,99 @@
+use crate::{
+    repositories::cache::Cache,
+    websocket::{messages::ServerMessage, server::CharadeServer},
+};
+
+use super::utils::{Result, ServerError, ServerResult};
+
+impl CharadeServer {
+    /// Tells every waitlisted user their current position in the queue
+    pub async fn waitlist_positions(&self, session_id: &str) -> Result {
+        let waitlist = self.db.get_waitlist(session_id).await.map_err(|err| {
+            log::error!("Could not get waitlist of session {}: {}", session_id, err);
+            ServerError::None
+        })?;
+
+        Ok(ServerResult::Multiple(
+            waitlist
+                .into_iter()
+                .enumerate()
+                .map(|(index, id)| ServerResult::Private {
+                    id,
+                    msg: ServerMessage::WaitlistPosition {
+                        position: index as u32 + 1,
+                    },
+                })
+                .collect(),
+        ))
+    }
+
+    /// Only members can play, users on the waitlist have to wait for their promotion
+    pub async fn check_membership(&self, session_id: &str, user_id: &str) -> Result<()> {
+        let is_member = self
+            .db
+            .is_session_member(session_id, user_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        if is_member {
+            return Ok(());
+        }
+
+        let position = self
+            .db
+            .get_waitlist_position(session_id, user_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        match position {
+            Some(position) => Err(ServerError::private(
+                user_id,
+                &format!("You are number {position} on the waitlist of this session"),
+            )),
+            None => Err(ServerError::private(user_id, "Join the session first")),
+        }
+    }
+
+    /// Members get the broadcasts of the session, waitlisted users only their position until they are promoted
+    pub async fn connect_to_session(&self, session_id: &str, user_id: &str) -> Result {
+        let is_member = self
+            .db
+            .is_session_member(session_id, user_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        if is_member {
+            self.cache
+                .push_strings_to_set(&Cache::session_users_key(session_id), vec![user_id])
+                .await
+                .map_err(|err| ServerError::internal(user_id, err))?;
+
+            let users = self.handle_update_users(session_id).await?;
+
+            return Ok(ServerResult::Broadcast {
+                session_id: session_id.to_string(),
+                msg: users,
+                exclude: None,
+            });
+        }
+
+        let position = self
+            .db
+            .get_waitlist_position(session_id, user_id)
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?
+            .ok_or_else(|| ServerError::private(user_id, "Join the session first"))?;
+
+        self.cache
+            .push_strings_to_set(&Cache::session_waiting_users_key(session_id), vec![user_id])
+            .await
+            .map_err(|err| ServerError::internal(user_id, err))?;
+
+        Ok(ServerResult::Private {
+            id: user_id.to_string(),
+            msg: ServerMessage::WaitlistPosition {
+                position: position as u32,
+            },
+        })
+    }
+}
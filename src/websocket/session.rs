@@ -4,8 +4,12 @@ use actix::prelude::*;
 use actix_web_actors::ws;
 
 use crate::{
-    repositories::database::Database,
-    websocket::messages::{self, ClientMessage, ClientMessageWrapper},
+    models::custom_api_errors::Result as ApiResult,
+    websocket::{
+        encoding::{Encoding, Frame},
+        messages::{self, ClientMessage, ClientMessageWrapper, ServerMessage},
+        rate_limiter::RateLimiter,
+    },
 };
 
 use super::server;
@@ -30,7 +34,11 @@ pub struct WsCharadeSession {
     /// Chat server
     pub server: Addr<server::CharadeServer>,
 
-    pub db: Database,
+    /// encoding used for outgoing messages
+    pub encoding: Encoding,
+
+    /// limits the client messages of this connection
+    pub rate_limiter: RateLimiter,
 }
 
 impl WsCharadeSession {
@@ -60,6 +68,40 @@ impl WsCharadeSession {
             ctx.ping(b"");
         });
     }
+
+    fn send_message(&self, msg: &ServerMessage, ctx: &mut ws::WebsocketContext<Self>) {
+        match self.encoding.encode(msg) {
+            Ok(Frame::Text(text)) => ctx.text(text),
+            Ok(Frame::Binary(bytes)) => ctx.binary(bytes),
+            Err(err) => log::error!("Could not encode server message: {}", err),
+        }
+    }
+
+    /// forwards a decoded client message to the charade server
+    fn forward_client_message(
+        &mut self,
+        msg: ApiResult<ClientMessage>,
+        ctx: &mut ws::WebsocketContext<Self>,
+    ) {
+        match msg {
+            Ok(msg) => {
+                if let Err(retry_after) = self.rate_limiter.check(&msg) {
+                    let reply = ServerMessage::rate_limited(msg.message_type(), retry_after);
+                    self.send_message(&reply, ctx);
+                    return;
+                }
+
+                self.server.do_send(ClientMessageWrapper {
+                    id: self.id.clone(),
+                    session_id: self.session_id.clone(),
+                    message: msg,
+                });
+            }
+            Err(err) => {
+                log::warn!("Could not decode client message: {}", err);
+            }
+        }
+    }
 }
 
 impl Actor for WsCharadeSession {
@@ -100,15 +142,7 @@ impl Handler<messages::ServerMessage> for WsCharadeSession {
     type Result = ();
 
     fn handle(&mut self, msg: messages::ServerMessage, ctx: &mut Self::Context) {
-        ctx.text(serde_json::to_string(&msg).unwrap());
-    }
-}
-
-impl Handler<messages::Message> for WsCharadeSession {
-    type Result = ();
-
-    fn handle(&mut self, msg: messages::Message, ctx: &mut Self::Context) {
-        ctx.text(msg.0);
+        self.send_message(&msg, ctx);
     }
 }
 
@@ -132,22 +166,11 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WsCharadeSession
                 self.hb = Instant::now();
             }
             ws::Message::Text(text) => {
-                let message_result = serde_json::from_str::<ClientMessage>(&text);
-
-                match message_result {
-                    Ok(msg) => {
-                        self.server.do_send(ClientMessageWrapper {
-                            id: self.id.clone(),
-                            session_id: self.session_id.clone(),
-                            message: msg,
-                        });
-                    }
-                    Err(_) => {
-                        println!("Not a json message");
-                    }
-                }
+                self.forward_client_message(Encoding::decode_text(&text), ctx);
+            }
+            ws::Message::Binary(bytes) => {
+                self.forward_client_message(Encoding::decode_binary(&bytes), ctx);
             }
-            ws::Message::Binary(_) => println!("Unexpected binary"),
             ws::Message::Close(reason) => {
                 ctx.close(reason);
                 ctx.stop();